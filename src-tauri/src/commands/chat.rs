@@ -22,8 +22,10 @@ use crate::commands::pty::*;
 use crate::commands::env::*;
 use crate::commands::config::*;
 use crate::commands::fs::*;
+use crate::commands::ollama::*;
 use crate::commands::skills::*;
 use crate::commands::utils::*;
+use crate::commands::secrets::*;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ModelEntry {
@@ -73,9 +75,14 @@ pub struct ChatMessage {
     pub timestamp: u64,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LlmMessage {
+    pub role: String, // "system", "user" or "assistant"
+    pub content: String,
+}
+
 pub fn get_sessions_path(app: &AppHandle) -> std::path::PathBuf {
-    let app_dir = app.path().app_config_dir().unwrap();
-    app_dir.join("sessions.json")
+    get_config_dir(app).join("sessions.json")
 }
 
 #[tauri::command]
@@ -93,14 +100,8 @@ pub fn get_chat_sessions(app: AppHandle) -> Result<Vec<ChatSession>, String> {
 #[tauri::command]
 pub fn save_chat_sessions(app: AppHandle, sessions: Vec<ChatSession>) -> Result<(), String> {
     let sessions_path = get_sessions_path(&app);
-    let dir = sessions_path.parent().unwrap();
-    if !dir.exists() {
-        fs::create_dir_all(dir).map_err(|e| e.to_string())?;
-    }
-
     let content = serde_json::to_string_pretty(&sessions).map_err(|e| e.to_string())?;
-    fs::write(sessions_path, content).map_err(|e| e.to_string())?;
-    Ok(())
+    atomic_write(&sessions_path, content.as_bytes())
 }
 
 #[tauri::command]
@@ -108,13 +109,20 @@ pub async fn fetch_remote_models(
     app: AppHandle,
     base_url: String,
     api_key: String,
+    api_key_id: Option<String>,
     api_type: Option<String>,
 ) -> Result<Vec<String>, String> {
+    let api_key = resolve_api_key(&app, Some(api_key), api_key_id, &base_url, api_type.as_deref()).await?;
+
     // Ensure base_url ends with /v1 or /v1/, adjust if necessary
     // Actually, usually users provide "https://api.openai.com/v1"
     // We want to fetch "{base_url}/models".
     // If base_url ends with slash, remove it first
-    let clean_base = base_url.trim_end_matches('/');
+    let clean_base = if base_url.trim().is_empty() {
+        ChatProvider::from_api_type(api_type.as_deref()).default_base_url()
+    } else {
+        base_url.trim_end_matches('/')
+    };
     let url = format!("{}/models", clean_base);
 
     println!("Fetching models from: {}", url);
@@ -183,6 +191,340 @@ pub async fn fetch_remote_models(
     Ok(model_ids)
 }
 
+/// Pulls complete lines out of a streamed response buffer, leaving any partial
+/// line in place until the next chunk arrives.
+pub fn drain_lines(buffer: &mut Vec<u8>) -> Vec<String> {
+    let mut lines = Vec::new();
+    while let Some(pos) = buffer.iter().position(|b| *b == b'\n') {
+        let line: Vec<u8> = buffer.drain(..=pos).collect();
+        let line = String::from_utf8_lossy(&line);
+        lines.push(line.trim_end_matches(['\r', '\n']).to_string());
+    }
+    lines
+}
+
+/// Returns the payload of an SSE `data:` line, or None for comments, event names and blanks.
+pub fn sse_data(line: &str) -> Option<&str> {
+    line.strip_prefix("data:").map(|d| d.trim_start())
+}
+
+pub fn emit_chat_delta(app: &AppHandle, request_id: &str, delta: &str) {
+    let _ = app.emit(
+        "chat-stream",
+        serde_json::json!({
+            "id": request_id,
+            "delta": delta,
+            "done": false
+        }),
+    );
+}
+
+pub fn emit_chat_done(app: &AppHandle, request_id: &str, error: Option<&str>) {
+    let _ = app.emit(
+        "chat-stream",
+        serde_json::json!({
+            "id": request_id,
+            "delta": "",
+            "done": true,
+            "error": error
+        }),
+    );
+}
+
+/// Chat backends we can talk to natively, selected by the same `api_type`
+/// values `fetch_remote_models` understands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChatProvider {
+    OpenAi,
+    Anthropic,
+    Google,
+    Ollama,
+}
+
+impl ChatProvider {
+    pub fn from_api_type(api_type: Option<&str>) -> Self {
+        match api_type {
+            Some("anthropic") => ChatProvider::Anthropic,
+            Some("google") => ChatProvider::Google,
+            Some("ollama") => ChatProvider::Ollama,
+            _ => ChatProvider::OpenAi,
+        }
+    }
+
+    pub fn default_base_url(&self) -> &'static str {
+        match self {
+            ChatProvider::OpenAi => "https://api.openai.com/v1",
+            ChatProvider::Anthropic => "https://api.anthropic.com/v1",
+            ChatProvider::Google => "https://generativelanguage.googleapis.com/v1beta",
+            ChatProvider::Ollama => "http://localhost:11434",
+        }
+    }
+
+    /// Returns the JSON payload of one streamed line. Ollama streams NDJSON,
+    /// everything else uses SSE `data:` lines.
+    pub fn frame<'a>(&self, line: &'a str) -> Option<&'a str> {
+        match self {
+            ChatProvider::Ollama => Some(line.trim()).filter(|l| !l.is_empty()),
+            _ => sse_data(line),
+        }
+    }
+
+    /// Builds a streaming request in the provider's native wire format.
+    pub fn build_request(
+        &self,
+        client: &reqwest::Client,
+        base_url: &str,
+        api_key: &str,
+        model: &str,
+        messages: &[LlmMessage],
+    ) -> reqwest::RequestBuilder {
+        let clean_base = if base_url.is_empty() {
+            self.default_base_url()
+        } else {
+            base_url.trim_end_matches('/')
+        };
+
+        match self {
+            ChatProvider::OpenAi => {
+                let url = format!("{}/chat/completions", clean_base);
+                let request = client.post(&url).json(&serde_json::json!({
+                    "model": model,
+                    "messages": messages,
+                    "stream": true
+                }));
+                // Local OpenAI-compatible servers usually run without a key
+                if api_key.is_empty() {
+                    request
+                } else {
+                    request.header("Authorization", format!("Bearer {}", api_key))
+                }
+            }
+            ChatProvider::Anthropic => {
+                let url = format!("{}/messages", clean_base);
+                let (system, turns) = split_system(messages);
+                let mut body = serde_json::json!({
+                    "model": model,
+                    "max_tokens": 4096,
+                    "messages": merge_turns(&turns, "user", "assistant")
+                        .into_iter()
+                        .map(|(role, text)| serde_json::json!({ "role": role, "content": text }))
+                        .collect::<Vec<_>>(),
+                    "stream": true
+                });
+                if let Some(system) = system {
+                    body["system"] = serde_json::Value::String(system);
+                }
+                client
+                    .post(&url)
+                    .header("x-api-key", api_key)
+                    .header("anthropic-version", "2023-06-01")
+                    .json(&body)
+            }
+            ChatProvider::Google => {
+                // Model ids from the models endpoint may still carry the "models/" prefix
+                let model = model.trim_start_matches("models/");
+                let url = format!(
+                    "{}/models/{}:streamGenerateContent?alt=sse",
+                    clean_base, model
+                );
+                let (system, turns) = split_system(messages);
+                let mut body = serde_json::json!({
+                    "contents": merge_turns(&turns, "user", "model")
+                        .into_iter()
+                        .map(|(role, text)| serde_json::json!({ "role": role, "parts": [{ "text": text }] }))
+                        .collect::<Vec<_>>()
+                });
+                if let Some(system) = system {
+                    body["systemInstruction"] = serde_json::json!({ "parts": [{ "text": system }] });
+                }
+                client
+                    .post(&url)
+                    .header("x-goog-api-key", api_key)
+                    .json(&body)
+            }
+            ChatProvider::Ollama => {
+                let url = format!("{}/api/chat", clean_base);
+                client.post(&url).json(&serde_json::json!({
+                    "model": model,
+                    "messages": messages,
+                    "stream": true
+                }))
+            }
+        }
+    }
+
+    /// Extracts the text delta from one streamed event, if it carries any.
+    pub fn parse_event(&self, event: &serde_json::Value) -> Result<Option<String>, String> {
+        if let Some(message) = event["error"]["message"].as_str() {
+            return Err(format!("API Error: {}", message));
+        }
+        // Ollama reports errors as a bare string
+        if let Some(message) = event["error"].as_str() {
+            return Err(format!("API Error: {}", message));
+        }
+
+        let delta = match self {
+            ChatProvider::OpenAi => event["choices"][0]["delta"]["content"]
+                .as_str()
+                .map(|s| s.to_string()),
+            ChatProvider::Anthropic => {
+                if event["type"] == "content_block_delta" {
+                    event["delta"]["text"].as_str().map(|s| s.to_string())
+                } else {
+                    None
+                }
+            }
+            ChatProvider::Google => event["candidates"][0]["content"]["parts"]
+                .as_array()
+                .map(|parts| {
+                    parts
+                        .iter()
+                        .filter_map(|p| p["text"].as_str())
+                        .collect::<String>()
+                }),
+            ChatProvider::Ollama => event["message"]["content"]
+                .as_str()
+                .map(|s| s.to_string()),
+        };
+
+        Ok(delta.filter(|d| !d.is_empty()))
+    }
+}
+
+/// Anthropic and Gemini take the system prompt outside the message list.
+fn split_system(messages: &[LlmMessage]) -> (Option<String>, Vec<&LlmMessage>) {
+    let system: Vec<&str> = messages
+        .iter()
+        .filter(|m| m.role == "system")
+        .map(|m| m.content.as_str())
+        .collect();
+    let turns = messages.iter().filter(|m| m.role != "system").collect();
+
+    if system.is_empty() {
+        (None, turns)
+    } else {
+        (Some(system.join("\n\n")), turns)
+    }
+}
+
+/// Maps roles to the provider's names and merges consecutive turns from the same
+/// side, since both Anthropic and Gemini reject non-alternating histories.
+fn merge_turns(
+    turns: &[&LlmMessage],
+    user_role: &'static str,
+    assistant_role: &'static str,
+) -> Vec<(&'static str, String)> {
+    let mut merged: Vec<(&'static str, String)> = Vec::new();
+    for msg in turns {
+        let role = if msg.role == "assistant" {
+            assistant_role
+        } else {
+            user_role
+        };
+        match merged.last_mut() {
+            Some((last_role, text)) if *last_role == role => {
+                text.push_str("\n\n");
+                text.push_str(&msg.content);
+            }
+            _ => merged.push((role, msg.content.clone())),
+        }
+    }
+    merged
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn chat_completion(
+    app: AppHandle,
+    api_key: String,
+    base_url: String,
+    model: String,
+    messages: Vec<LlmMessage>,
+    api_type: Option<String>,
+    request_id: Option<String>,
+    api_key_id: Option<String>,
+) -> Result<String, String> {
+    // Deltas are emitted as "chat-stream" events keyed by request id so the
+    // renderer can match them to the message being streamed.
+    let request_id = request_id.unwrap_or_else(|| {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_millis().to_string())
+            .unwrap_or_default()
+    });
+
+    // Every failure, including a missing key, has to end the stream for the renderer
+    let result = async {
+        let api_key = resolve_api_key(&app, Some(api_key), api_key_id, &base_url, api_type.as_deref()).await?;
+        let provider = ChatProvider::from_api_type(api_type.as_deref());
+        stream_chat(&app, provider, &request_id, &api_key, &base_url, &model, &messages).await
+    }
+    .await;
+    emit_chat_done(&app, &request_id, result.as_ref().err().map(|e| e.as_str()));
+    result
+}
+
+async fn stream_chat(
+    app: &AppHandle,
+    provider: ChatProvider,
+    request_id: &str,
+    api_key: &str,
+    base_url: &str,
+    model: &str,
+    messages: &[LlmMessage],
+) -> Result<String, String> {
+    let (client, base_url) = if provider == ChatProvider::Ollama {
+        let base = if base_url.is_empty() {
+            get_local_ai_base_url(app)
+        } else {
+            base_url.to_string()
+        };
+        (get_local_ai_client(app, &base)?, base)
+    } else {
+        (get_proxy_client(app)?, base_url.to_string())
+    };
+    let request = provider.build_request(&client, &base_url, api_key, model, messages);
+
+    let res = request
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {}", e))?;
+
+    if !res.status().is_success() {
+        let status = res.status();
+        let body = res.text().await.unwrap_or_default();
+        return Err(format!("API Error: {} {}", status, body));
+    }
+
+    let mut stream = res.bytes_stream();
+    let mut buffer: Vec<u8> = Vec::new();
+    let mut reply = String::new();
+
+    while let Some(item) = stream.next().await {
+        let chunk = item.map_err(|e| format!("Error while reading stream: {}", e))?;
+        buffer.extend_from_slice(&chunk);
+
+        for line in drain_lines(&mut buffer) {
+            let Some(data) = provider.frame(&line) else { continue };
+            if data == "[DONE]" {
+                return Ok(reply);
+            }
+
+            let event: serde_json::Value = match serde_json::from_str(data) {
+                Ok(v) => v,
+                Err(_) => continue,
+            };
+
+            if let Some(delta) = provider.parse_event(&event)? {
+                reply.push_str(&delta);
+                emit_chat_delta(app, request_id, &delta);
+            }
+        }
+    }
+
+    Ok(reply)
+}
+
 #[tauri::command]
 pub fn get_models(provider: String) -> Result<Vec<String>, String> {
     let output = if cfg!(target_os = "windows") {
@@ -20,20 +20,31 @@ use std::os::windows::process::CommandExt;
 
 use crate::commands::pty::*;
 use crate::commands::env::*;
+use crate::commands::probes::*;
 use crate::commands::fs::*;
 use crate::commands::chat::*;
+use crate::commands::config_file::*;
 use crate::commands::skills::*;
 use crate::commands::utils::*;
+use crate::commands::registry::*;
+use crate::commands::secrets::*;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ToolConfig {
     pub working_directory: Option<String>,
     #[serde(rename = "llmApiKey")]
     pub llm_api_key: Option<String>,
+    /// Id of the API key in the secret store; `llm_api_key` is only set transiently
+    /// by the renderer when the user enters a new key.
+    #[serde(rename = "llmApiKeyId")]
+    pub llm_api_key_id: Option<String>,
     #[serde(rename = "llmBaseUrl")]
     pub llm_base_url: Option<String>,
     #[serde(rename = "llmModel")]
     pub llm_model: Option<String>,
+    /// Chat wire format: "openai" (default), "anthropic", "google" or "ollama".
+    #[serde(rename = "llmApiType")]
+    pub llm_api_type: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -43,8 +54,22 @@ pub struct CommandPreset {
     pub command: String,
 }
 
+/// A named terminal setup, e.g. `claude` in repo B with its own API key env.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TerminalProfile {
+    pub id: String,
+    pub name: String,
+    pub program: Option<String>,
+    pub args: Option<Vec<String>>,
+    pub cwd: Option<String>,
+    pub env: Option<std::collections::HashMap<String, String>>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct AppConfig {
+    // Bumped whenever a stored field changes shape; see CONFIG_MIGRATIONS
+    #[serde(default)]
+    pub schema_version: u32,
     pub proxy_type: Option<String>,
     pub proxy_address: Option<String>,
     pub theme: Option<String>,
@@ -63,7 +88,6 @@ pub struct AppConfig {
     pub terminal_shell: Option<String>,
     pub current_directory: Option<String>,
     pub active_tool_id: Option<String>,
-    pub env_status: Option<EnvironmentStatus>,
     pub tool_statuses: Option<std::collections::HashMap<String, ToolStatus>>,
     pub tool_configs: Option<std::collections::HashMap<String, ToolConfig>>,
     pub command_presets: Option<Vec<CommandPreset>>,
@@ -75,11 +99,17 @@ pub struct AppConfig {
     pub local_ai_provider: Option<String>,
     pub ide_path: Option<String>,
     pub chat_providers: Option<Vec<String>>,
+    pub allowed_directories: Option<Vec<String>>,
+    pub terminal_profiles: Option<Vec<TerminalProfile>>,
+    pub environment_probes: Option<Vec<ProbeDefinition>>,
+    // NodeInstall id used for tool installs and launches, e.g. "nvm@20.11.0"
+    pub node_install: Option<String>,
 }
 
 impl Default for AppConfig {
     fn default() -> Self {
         Self {
+            schema_version: CONFIG_SCHEMA_VERSION,
             proxy_type: Some("none".to_string()),
             proxy_address: None,
             theme: Some("light".to_string()),
@@ -106,7 +136,6 @@ impl Default for AppConfig {
             },
             current_directory: None,
             active_tool_id: None,
-            env_status: None,
             tool_statuses: None,
             tool_configs: Some(std::collections::HashMap::new()),
             global_instructions: None,
@@ -118,72 +147,296 @@ impl Default for AppConfig {
             resource_sidebar_width: Some(280),
             active_chat_tool_id: None,
             chat_providers: None,
+            allowed_directories: None,
+            terminal_profiles: None,
+            environment_probes: None,
+            node_install: None,
         }
     }
 }
 
-pub fn get_config_path(_app: &AppHandle) -> PathBuf {
-    let mut path = std::env::current_exe().expect("failed to get current exe path");
+/// A file with this name next to the executable keeps config beside it (portable mode).
+const PORTABLE_MARKER: &str = "portable";
+
+fn exe_dir() -> Option<PathBuf> {
+    let mut path = std::env::current_exe().ok()?;
     path.pop(); // Remove exe name
-    path.push("app");
-    path.push("config");
+    Some(path)
+}
+
+/// Where config lived before it moved to the platform config dir; still used in portable mode.
+fn exe_config_dir() -> Option<PathBuf> {
+    exe_dir().map(|dir| dir.join("app").join("config"))
+}
+
+pub fn is_portable() -> bool {
+    exe_dir()
+        .map(|dir| dir.join(PORTABLE_MARKER).exists())
+        .unwrap_or(false)
+}
+
+/// Directory holding config.json, tools.json and sessions.json: the platform app
+/// config dir, or `<exe dir>/app/config` in portable mode.
+pub fn get_config_dir(app: &AppHandle) -> PathBuf {
+    let portable_dir = exe_config_dir().filter(|_| is_portable());
+    let path = portable_dir
+        .or_else(|| app.path().app_config_dir().ok())
+        .or_else(exe_config_dir)
+        .expect("failed to resolve a config directory");
 
     // Ensure directory exists
     if !path.exists() {
         let _ = fs::create_dir_all(&path);
     }
+    path
+}
+
+pub fn get_config_path(app: &AppHandle) -> PathBuf {
+    get_config_dir(app).join("config.json")
+}
+
+/// Copies config written next to the executable by older versions into the
+/// platform config dir. Runs once: it's skipped as soon as the new config exists.
+pub fn migrate_exe_relative_config(app: &AppHandle) {
+    if is_portable() {
+        return;
+    }
+    let (Some(old_dir), Ok(new_dir)) = (exe_config_dir(), app.path().app_config_dir()) else {
+        return;
+    };
+    if !old_dir.join("config.json").exists() || new_dir.join("config.json").exists() {
+        return;
+    }
+
+    println!("Migrating config from {:?} to {:?}", old_dir, new_dir);
+    if let Err(e) = fs::create_dir_all(&new_dir) {
+        println!("Config migration failed: {}", e);
+        return;
+    }
+    // config.json last, so an interrupted migration is retried on the next start
+    for name in ["tools.json", "config.json"] {
+        let from = old_dir.join(name);
+        if from.exists() {
+            if let Err(e) = fs::copy(&from, new_dir.join(name)) {
+                println!("Failed to migrate {}: {}", name, e);
+                return;
+            }
+        }
+    }
+}
+
+/// Current `AppConfig.schema_version`. Files written before versioning are version 0.
+pub const CONFIG_SCHEMA_VERSION: u32 = 2;
+
+/// `CONFIG_MIGRATIONS[n]` upgrades a config object from version n to n + 1.
+const CONFIG_MIGRATIONS: &[fn(&mut serde_json::Map<String, serde_json::Value>)] =
+    &[migrate_v0_to_v1, migrate_v1_to_v2];
+
+/// v1: environment probe results became `{ status, version, error }` objects.
+/// v2 drops `env_status` altogether, so there is nothing left to convert here.
+fn migrate_v0_to_v1(_config: &mut serde_json::Map<String, serde_json::Value>) {}
+
+/// v2: environment probe results are no longer cached in the config; they go
+/// stale as soon as the user installs something and are re-probed on start.
+fn migrate_v1_to_v2(config: &mut serde_json::Map<String, serde_json::Value>) {
+    config.remove("env_status");
+}
+
+fn is_valid_config(value: &serde_json::Map<String, serde_json::Value>) -> bool {
+    serde_json::from_value::<AppConfig>(serde_json::Value::Object(value.clone())).is_ok()
+}
+
+/// Lays the loaded fields over the defaults one at a time, keeping each one that
+/// still deserializes. An object field that fails as a whole (e.g. `tool_configs`
+/// with one bad entry) is retried entry by entry. Returns the names of anything dropped.
+fn merge_config_fields(
+    merged: &mut serde_json::Map<String, serde_json::Value>,
+    loaded: serde_json::Map<String, serde_json::Value>,
+) -> Vec<String> {
+    let mut dropped = Vec::new();
+    for (key, value) in loaded {
+        let previous = merged.insert(key.clone(), value.clone());
+        if is_valid_config(merged) {
+            continue;
+        }
+
+        let restore = |merged: &mut serde_json::Map<String, serde_json::Value>| match &previous {
+            Some(previous) => merged.insert(key.clone(), previous.clone()),
+            None => merged.remove(&key),
+        };
+        let serde_json::Value::Object(entries) = value else {
+            restore(merged);
+            dropped.push(key);
+            continue;
+        };
+
+        merged.insert(key.clone(), serde_json::Value::Object(serde_json::Map::new()));
+        if !is_valid_config(merged) {
+            restore(merged);
+            dropped.push(key);
+            continue;
+        }
+        for (entry_key, entry) in entries {
+            let serde_json::Value::Object(field) = merged.get_mut(&key).unwrap() else {
+                break;
+            };
+            field.insert(entry_key.clone(), entry);
+            if !is_valid_config(merged) {
+                if let Some(serde_json::Value::Object(field)) = merged.get_mut(&key) {
+                    field.remove(&entry_key);
+                }
+                dropped.push(format!("{}.{}", key, entry_key));
+            }
+        }
+    }
+    dropped
+}
+
+/// Copies the config file aside before it gets rewritten with what could be recovered.
+fn backup_config_file(path: &std::path::Path) {
+    let ts = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    let backup = path.with_file_name(format!("config.json.bak-{}", ts));
+    match fs::copy(path, &backup) {
+        Ok(_) => println!("Backed up config to {:?}", backup),
+        Err(e) => println!("Failed to back up config to {:?}: {}", backup, e),
+    }
+}
+
+/// Parses a stored config: runs migrations up to the current schema, then merges
+/// field by field so one bad value doesn't reset every setting. Returns the config
+/// and whether it differs from what was stored (and so should be written back).
+fn load_config(content: &str, path: &std::path::Path) -> (AppConfig, bool) {
+    let mut loaded = match serde_json::from_str::<serde_json::Value>(content) {
+        Ok(serde_json::Value::Object(map)) => map,
+        Ok(_) | Err(_) => {
+            println!("Config at {:?} is not a JSON object; starting from defaults", path);
+            backup_config_file(path);
+            return (AppConfig::default(), true);
+        }
+    };
+
+    let version = loaded
+        .get("schema_version")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0) as usize;
+    // Written by a newer build; keep a copy before this one rewrites it as the
+    // current version and drops whatever it doesn't understand
+    let downgraded = version > CONFIG_MIGRATIONS.len();
+    if downgraded {
+        println!(
+            "Warning: config schema version {} is newer than {}; loading it as version {}",
+            version, CONFIG_SCHEMA_VERSION, CONFIG_SCHEMA_VERSION
+        );
+        backup_config_file(path);
+    }
+    for migrate in CONFIG_MIGRATIONS.iter().skip(version) {
+        migrate(&mut loaded);
+    }
+    let migrated = version < CONFIG_MIGRATIONS.len();
+    loaded.insert("schema_version".to_string(), CONFIG_SCHEMA_VERSION.into());
 
-    path.join("config.json")
+    let mut merged = match serde_json::to_value(AppConfig::default()) {
+        Ok(serde_json::Value::Object(map)) => map,
+        _ => serde_json::Map::new(),
+    };
+    let dropped = merge_config_fields(&mut merged, loaded);
+    if !dropped.is_empty() {
+        println!("Dropped invalid config fields: {}", dropped.join(", "));
+        backup_config_file(path);
+    }
+
+    let config = serde_json::from_value(serde_json::Value::Object(merged)).unwrap_or_default();
+    (config, migrated || downgraded || !dropped.is_empty())
 }
 
 #[tauri::command]
 pub fn get_app_config(app: AppHandle) -> AppConfig {
     let config_path = get_config_path(&app);
-    if config_path.exists() {
-        let content = fs::read_to_string(config_path).unwrap_or_default();
-        // Use serde_json to parse. If it fails or is partial, we should merge with default
-        let loaded_config: Option<AppConfig> = serde_json::from_str(&content).ok();
-
-        if let Some(config) = loaded_config {
-            // Ensure essential fields are not None if they shouldn't be (merging logic)
-            // For now, simpler: if loaded successfully, return it. logic for active_tool_id is Option<String> so None is valid.
-            config
-        } else {
-            // Failed to parse, return default
-            AppConfig::default()
+    if !config_path.exists() {
+        return AppConfig::default();
+    }
+
+    let content = match fs::read_to_string(&config_path) {
+        Ok(content) => content,
+        Err(e) => {
+            println!("Failed to read config {:?}: {}", config_path, e);
+            return AppConfig::default();
+        }
+    };
+    let (config, changed) = load_config(&content, &config_path);
+    // Write the recovered/migrated config back so this only happens once
+    if changed {
+        if let Err(e) = write_app_config(&app, &config) {
+            println!("Failed to write recovered config: {}", e);
         }
-    } else {
-        AppConfig::default()
     }
+    config
 }
 
-#[tauri::command]
-pub fn save_app_config(app: AppHandle, config: AppConfig) -> Result<(), String> {
-    let config_path = get_config_path(&app);
-    let dir = config_path.parent().unwrap();
-    if !dir.exists() {
-        fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+/// Restores fields that only the backend writes when the renderer saves a config;
+/// the renderer's copy of them is never trusted.
+pub fn preserve_backend_fields(config: &mut AppConfig, existing: AppConfig) {
+    // Only backend commands write these; whatever the renderer sends may be a
+    // stale copy from before an `allow_directory`, profile or probe save, or Node switch
+    config.allowed_directories = existing.allowed_directories;
+    config.terminal_profiles = existing.terminal_profiles;
+    config.environment_probes = existing.environment_probes;
+    config.node_install = existing.node_install;
+
+    // The renderer round-trips tool statuses it fetched earlier, which may predate
+    // an install the backend recorded since
+    if let Some(existing_statuses) = existing.tool_statuses {
+        let statuses = config.tool_statuses.get_or_insert_with(HashMap::new);
+        for (id, previous) in existing_statuses {
+            match statuses.get_mut(&id) {
+                Some(status) => {
+                    status.install_method = previous.install_method;
+                    status.previous_version = previous.previous_version;
+                }
+                None => {
+                    statuses.insert(id, previous);
+                }
+            }
+        }
+    }
+
+    // Key ids are assigned by the backend when a key is stored
+    if let (Some(configs), Some(existing_configs)) =
+        (config.tool_configs.as_mut(), existing.tool_configs.as_ref())
+    {
+        for (key, tool_config) in configs.iter_mut() {
+            if tool_config.llm_api_key_id.is_none() {
+                if let Some(previous) = existing_configs.get(key) {
+                    tool_config.llm_api_key_id = previous.llm_api_key_id.clone();
+                }
+            }
+        }
     }
+}
+
+#[tauri::command]
+pub fn save_app_config(app: AppHandle, mut config: AppConfig) -> Result<(), String> {
+    preserve_backend_fields(&mut config, get_app_config(app.clone()));
+    store_tool_secrets(&app, &mut config)?;
+    write_app_config(&app, &config)
+}
+
+pub fn write_app_config(app: &AppHandle, config: &AppConfig) -> Result<(), String> {
+    let config_path = get_config_path(app);
 
+    // The renderer doesn't carry schema_version, so stamp it on every write
+    let mut config = config.clone();
+    config.schema_version = CONFIG_SCHEMA_VERSION;
     let content = serde_json::to_string_pretty(&config).map_err(|e| e.to_string())?;
-    fs::write(config_path, content).map_err(|e| e.to_string())?;
-    Ok(())
+    atomic_write(&config_path, content.as_bytes())
 }
 
 #[tauri::command]
 pub fn get_config_file(path: String) -> Result<String, String> {
-    let home = std::env::var("HOME")
-        .or_else(|_| std::env::var("USERPROFILE"))
-        .map_err(|_| "Could not determine home directory".to_string())?;
-
-    // Expand ~ manually if present at the start of the path
-    let expanded_path = if path.starts_with("~") {
-        path.replacen("~", &home, 1)
-    } else {
-        path
-    };
-
-    let p = PathBuf::from(expanded_path);
+    let p = expand_home(&path)?;
     if !p.exists() {
         return Ok("".to_string()); // Return empty string if file doesn't exist yet
     }
@@ -191,29 +444,66 @@ pub fn get_config_file(path: String) -> Result<String, String> {
     fs::read_to_string(p).map_err(|e| e.to_string())
 }
 
-#[tauri::command]
-pub fn save_config_file(path: String, content: String) -> Result<(), String> {
-    let home = std::env::var("HOME")
-        .or_else(|_| std::env::var("USERPROFILE"))
-        .map_err(|_| "Could not determine home directory".to_string())?;
-
-    // Expand ~ manually
-    let expanded_path = if path.starts_with("~") {
-        path.replacen("~", &home, 1)
-    } else {
-        path
-    };
+/// How many previous versions of each third-party tool config are kept.
+const TOOL_CONFIG_BACKUPS: usize = 5;
 
-    let p = PathBuf::from(expanded_path);
+/// Backups of third-party tool configs live in the app config dir rather than
+/// next to the files, so the user's home directory doesn't fill up with them.
+pub fn get_tool_config_backup_dir(app: &AppHandle) -> PathBuf {
+    get_config_dir(app).join("backups")
+}
 
-    // Ensure parent directory exists
-    if let Some(parent) = p.parent() {
-        if !parent.exists() {
-            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+/// Backup file prefix for a path, e.g. "/home/me/.claude/settings.json" ->
+/// "%2Fhome%2Fme%2F.claude%2Fsettings.json". Separators are percent-encoded (and
+/// so is '%' itself) so that distinct paths never share a backup slot.
+fn backup_name(path: &std::path::Path) -> String {
+    let mut name = String::new();
+    for c in path.to_string_lossy().chars() {
+        match c {
+            '%' => name.push_str("%25"),
+            '/' => name.push_str("%2F"),
+            '\\' => name.push_str("%5C"),
+            ':' => name.push_str("%3A"),
+            c => name.push(c),
         }
     }
+    name
+}
+
+/// Writes a third-party tool config, keeping the previous versions as backups.
+pub fn write_config_file(app: &AppHandle, p: &std::path::Path, content: &str) -> Result<(), String> {
+    // Saving unchanged content shouldn't push a real backup out of the rotation
+    if fs::read_to_string(p).map(|old| old == content).unwrap_or(false) {
+        return Ok(());
+    }
 
-    fs::write(p, content).map_err(|e| e.to_string())
+    rotate_backups(
+        p,
+        &get_tool_config_backup_dir(app),
+        &backup_name(p),
+        TOOL_CONFIG_BACKUPS,
+    )?;
+    atomic_write(p, content.as_bytes())
+}
+
+/// Saves raw content, refusing anything that doesn't parse as the file's format
+/// (given explicitly or by extension) so a typo can't break the tool. Parse
+/// errors keep their line and column for the editor to point at.
+#[tauri::command]
+pub fn save_config_file(
+    app: AppHandle,
+    path: String,
+    content: String,
+    format: Option<ConfigFormat>,
+) -> Result<(), ConfigFileError> {
+    let p = expand_home(&path)?;
+    if let Some(format) = format.or_else(|| ConfigFormat::from_path(&p)) {
+        parse_config(format, &content).map_err(|e| ConfigFileError {
+            message: format!("Invalid {}: {}", format.label(), e.message),
+            ..e
+        })?;
+    }
+    Ok(write_config_file(&app, &p, &content)?)
 }
 
 #[tauri::command]
@@ -302,3 +592,99 @@ pub fn get_system_fonts() -> Vec<String> {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn backups(dir: &std::path::Path) -> Vec<String> {
+        fs::read_dir(dir)
+            .unwrap()
+            .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+            .filter(|name| name.starts_with("config.json.bak-"))
+            .collect()
+    }
+
+    fn load(dir: &std::path::Path, content: &str) -> (AppConfig, bool) {
+        let path = dir.join("config.json");
+        fs::write(&path, content).unwrap();
+        load_config(content, &path)
+    }
+
+    #[test]
+    fn bad_field_keeps_the_rest() {
+        let dir = tempfile::tempdir().unwrap();
+        let content = serde_json::json!({
+            "schema_version": CONFIG_SCHEMA_VERSION,
+            "theme": "dark",
+            "terminal_font_size": "big",
+            "tool_configs": {
+                "good": { "llmModel": "gpt-4o" },
+                "bad": 5
+            }
+        })
+        .to_string();
+        let (config, changed) = load(dir.path(), &content);
+
+        assert!(changed);
+        assert_eq!(config.theme.as_deref(), Some("dark"));
+        assert_eq!(config.terminal_font_size, AppConfig::default().terminal_font_size);
+        let tool_configs = config.tool_configs.unwrap();
+        assert_eq!(tool_configs["good"].llm_model.as_deref(), Some("gpt-4o"));
+        assert!(!tool_configs.contains_key("bad"));
+        assert_eq!(backups(dir.path()).len(), 1);
+    }
+
+    #[test]
+    fn migrates_v0_to_current() {
+        let dir = tempfile::tempdir().unwrap();
+        let content = r#"{"theme":"dark","env_status":{"node":"v20.11.0","git":null}}"#;
+        let (config, changed) = load(dir.path(), content);
+
+        assert!(changed);
+        assert_eq!(config.schema_version, CONFIG_SCHEMA_VERSION);
+        assert_eq!(config.theme.as_deref(), Some("dark"));
+        assert!(backups(dir.path()).is_empty());
+
+        let serde_json::Value::Object(mut map) = serde_json::from_str(content).unwrap() else {
+            unreachable!()
+        };
+        for migrate in CONFIG_MIGRATIONS {
+            migrate(&mut map);
+        }
+        assert!(!map.contains_key("env_status"));
+    }
+
+    #[test]
+    fn current_config_is_not_rewritten() {
+        let dir = tempfile::tempdir().unwrap();
+        let content = serde_json::to_string(&AppConfig::default()).unwrap();
+        let (_, changed) = load(dir.path(), &content);
+
+        assert!(!changed);
+        assert!(backups(dir.path()).is_empty());
+    }
+
+    #[test]
+    fn non_object_file_is_backed_up() {
+        let dir = tempfile::tempdir().unwrap();
+        let (config, changed) = load(dir.path(), "[1, 2]");
+
+        assert!(changed);
+        assert_eq!(config.theme, AppConfig::default().theme);
+        let backups = backups(dir.path());
+        assert_eq!(backups.len(), 1);
+        assert_eq!(fs::read_to_string(dir.path().join(&backups[0])).unwrap(), "[1, 2]");
+    }
+
+    #[test]
+    fn newer_schema_is_backed_up_before_downgrade() {
+        let dir = tempfile::tempdir().unwrap();
+        let content = serde_json::json!({ "schema_version": CONFIG_SCHEMA_VERSION + 1, "theme": "dark" }).to_string();
+        let (config, changed) = load(dir.path(), &content);
+
+        assert!(changed);
+        assert_eq!(config.schema_version, CONFIG_SCHEMA_VERSION);
+        assert_eq!(config.theme.as_deref(), Some("dark"));
+        assert_eq!(backups(dir.path()).len(), 1);
+    }
+}
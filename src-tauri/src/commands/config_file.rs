@@ -0,0 +1,845 @@
+#![allow(unused_imports)]
+#![allow(dead_code)]
+
+use futures_util::StreamExt;
+use portable_pty::{Child, CommandBuilder, MasterPty, NativePtySystem, PtySize, PtySystem};
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::cmp::min;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+#[cfg(windows)]
+use std::os::windows::process::CommandExt;
+
+use crate::commands::pty::*;
+use crate::commands::env::*;
+use crate::commands::config::*;
+use crate::commands::fs::*;
+use crate::commands::chat::*;
+use crate::commands::registry::*;
+use crate::commands::skills::*;
+use crate::commands::utils::*;
+
+use serde_json::Value as JsonValue;
+use serde_yaml::Value as YamlValue;
+use toml_edit::{DocumentMut, InlineTable, Item, Table};
+
+/// Formats a tool config can declare (`configType` in `TOOLS_METADATA`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConfigFormat {
+    Json,
+    #[serde(alias = "yml")]
+    Yaml,
+    Toml,
+}
+
+impl ConfigFormat {
+    pub fn label(self) -> &'static str {
+        match self {
+            ConfigFormat::Json => "JSON",
+            ConfigFormat::Yaml => "YAML",
+            ConfigFormat::Toml => "TOML",
+        }
+    }
+
+    pub fn from_path(path: &std::path::Path) -> Option<Self> {
+        match path.extension()?.to_str()?.to_ascii_lowercase().as_str() {
+            "json" => Some(ConfigFormat::Json),
+            "yaml" | "yml" => Some(ConfigFormat::Yaml),
+            "toml" => Some(ConfigFormat::Toml),
+            _ => None,
+        }
+    }
+
+    /// An explicit format wins over the file extension.
+    pub fn resolve(format: Option<ConfigFormat>, path: &std::path::Path) -> Result<Self, String> {
+        format
+            .or_else(|| Self::from_path(path))
+            .ok_or_else(|| format!("Unknown config format for {:?}", path))
+    }
+}
+
+/// A parse or edit failure; `line` and `column` are 1-based and point at the
+/// offending spot when the parser reports one.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigFileError {
+    pub message: String,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+}
+
+impl From<String> for ConfigFileError {
+    fn from(message: String) -> Self {
+        ConfigFileError { message, line: None, column: None }
+    }
+}
+
+impl std::fmt::Display for ConfigFileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.line, self.column) {
+            (Some(line), Some(column)) => write!(f, "line {}, column {}: {}", line, column, self.message),
+            (Some(line), None) => write!(f, "line {}: {}", line, self.message),
+            _ => write!(f, "{}", self.message),
+        }
+    }
+}
+
+/// 1-based line and column of a byte offset.
+fn location_at(content: &str, offset: usize) -> (usize, usize) {
+    let before = content.get(..offset).unwrap_or(content);
+    let line = before.matches('\n').count() + 1;
+    let column = before.rsplit('\n').next().unwrap_or("").chars().count() + 1;
+    (line, column)
+}
+
+/// serde_json and serde_yaml put " at line X column Y" in their messages (serde_yaml
+/// also mid-sentence, for the start of the enclosing node), which is already in
+/// the structured fields.
+fn strip_location(mut message: String) -> String {
+    while let Some(index) = message.rfind(" at line ") {
+        let rest = &message[index + " at line ".len()..];
+        let rest = rest.trim_start_matches(|c: char| c.is_ascii_digit());
+        let rest = rest
+            .strip_prefix(" column ")
+            .map(|r| r.trim_start_matches(|c: char| c.is_ascii_digit()))
+            .unwrap_or(rest);
+        message = format!("{}{}", &message[..index], rest);
+    }
+    message
+}
+
+/// A parsed config. TOML keeps its full syntax tree so comments and layout
+/// survive edits; JSON and YAML keep key order.
+pub enum ConfigDocument {
+    Json(JsonValue),
+    Yaml(YamlValue),
+    Toml(DocumentMut),
+}
+
+/// Parses `content`; a blank file is an empty document rather than an error,
+/// since tools create their configs lazily.
+pub fn parse_config(format: ConfigFormat, content: &str) -> Result<ConfigDocument, ConfigFileError> {
+    match format {
+        ConfigFormat::Json => {
+            if content.trim().is_empty() {
+                return Ok(ConfigDocument::Json(JsonValue::Object(Default::default())));
+            }
+            serde_json::from_str(content)
+                .map(ConfigDocument::Json)
+                .map_err(|e| ConfigFileError {
+                    message: strip_location(e.to_string()),
+                    line: Some(e.line()),
+                    column: Some(e.column()),
+                })
+        }
+        ConfigFormat::Yaml => {
+            if content.trim().is_empty() {
+                return Ok(ConfigDocument::Yaml(YamlValue::Mapping(Default::default())));
+            }
+            serde_yaml::from_str(content)
+                .map(ConfigDocument::Yaml)
+                .map_err(|e| {
+                    let location = e.location();
+                    ConfigFileError {
+                        message: strip_location(e.to_string()),
+                        line: location.as_ref().map(|l| l.line()),
+                        column: location.as_ref().map(|l| l.column()),
+                    }
+                })
+        }
+        ConfigFormat::Toml => content.parse::<DocumentMut>().map(ConfigDocument::Toml).map_err(|e| {
+            let location = e.span().map(|span| location_at(content, span.start));
+            ConfigFileError {
+                message: e.message().to_string(),
+                line: location.map(|(line, _)| line),
+                column: location.map(|(_, column)| column),
+            }
+        }),
+    }
+}
+
+impl ConfigDocument {
+    pub fn to_json(&self) -> Result<JsonValue, String> {
+        match self {
+            ConfigDocument::Json(value) => Ok(value.clone()),
+            ConfigDocument::Yaml(value) => serde_json::to_value(value).map_err(|e| e.to_string()),
+            ConfigDocument::Toml(doc) => Ok(toml_table_to_json(doc.as_table())),
+        }
+    }
+}
+
+fn toml_table_to_json(table: &Table) -> JsonValue {
+    JsonValue::Object(
+        table
+            .iter()
+            .map(|(key, item)| (key.to_string(), toml_item_to_json(item)))
+            .collect(),
+    )
+}
+
+fn toml_item_to_json(item: &Item) -> JsonValue {
+    match item {
+        Item::None => JsonValue::Null,
+        Item::Value(value) => toml_value_to_json(value),
+        Item::Table(table) => toml_table_to_json(table),
+        Item::ArrayOfTables(tables) => JsonValue::Array(tables.iter().map(toml_table_to_json).collect()),
+    }
+}
+
+fn toml_value_to_json(value: &toml_edit::Value) -> JsonValue {
+    use toml_edit::Value;
+    match value {
+        Value::String(s) => JsonValue::from(s.value().as_str()),
+        Value::Integer(i) => JsonValue::from(*i.value()),
+        Value::Float(f) => JsonValue::from(*f.value()),
+        Value::Boolean(b) => JsonValue::from(*b.value()),
+        // TOML datetimes have no JSON counterpart; RFC 3339 text is what the tools expect back
+        Value::Datetime(d) => JsonValue::from(d.value().to_string()),
+        Value::Array(items) => JsonValue::Array(items.iter().map(toml_value_to_json).collect()),
+        Value::InlineTable(table) => JsonValue::Object(
+            table
+                .iter()
+                .map(|(key, value)| (key.to_string(), toml_value_to_json(value)))
+                .collect(),
+        ),
+    }
+}
+
+fn json_to_toml(value: &JsonValue) -> Result<toml_edit::Value, String> {
+    Ok(match value {
+        JsonValue::Null => return Err("TOML has no null value; omit the value to remove the key".to_string()),
+        JsonValue::Bool(b) => (*b).into(),
+        JsonValue::Number(n) => match (n.as_i64(), n.as_f64()) {
+            (Some(i), _) => i.into(),
+            // Integers past i64 would silently round if stored as floats
+            (None, _) if n.is_u64() => return Err(format!("{} is too large for a TOML integer", n)),
+            (None, Some(f)) => f.into(),
+            _ => return Err(format!("{} is out of range for TOML", n)),
+        },
+        JsonValue::String(s) => s.as_str().into(),
+        JsonValue::Array(items) => {
+            let mut array = toml_edit::Array::new();
+            for item in items {
+                array.push(json_to_toml(item)?);
+            }
+            toml_edit::Value::Array(array)
+        }
+        JsonValue::Object(map) => {
+            let mut table = InlineTable::new();
+            for (key, value) in map {
+                table.insert(key, json_to_toml(value)?);
+            }
+            toml_edit::Value::InlineTable(table)
+        }
+    })
+}
+
+/// Splits a JSON-pointer-like path ("/mcpServers/github/command") into keys,
+/// unescaping `~1` and `~0` as in RFC 6901. The leading slash is optional.
+pub fn parse_config_path(path: &str) -> Vec<String> {
+    let path = path.strip_prefix('/').unwrap_or(path);
+    if path.is_empty() {
+        return Vec::new();
+    }
+    path.split('/')
+        .map(|key| key.replace("~1", "/").replace("~0", "~"))
+        .collect()
+}
+
+/// Array position for a path key; `-` (or the length) appends.
+fn array_index(key: &str, len: usize) -> Result<usize, String> {
+    if key == "-" {
+        return Ok(len);
+    }
+    match key.parse::<usize>() {
+        Ok(index) if index <= len => Ok(index),
+        Ok(index) => Err(format!("Index {} is out of bounds (length {})", index, len)),
+        Err(_) => Err(format!("\"{}\" is not an array index", key)),
+    }
+}
+
+pub fn get_json_path<'a>(root: &'a JsonValue, keys: &[String]) -> Option<&'a JsonValue> {
+    keys.iter().try_fold(root, |node, key| match node {
+        JsonValue::Object(map) => map.get(key),
+        JsonValue::Array(items) => key.parse::<usize>().ok().and_then(|i| items.get(i)),
+        _ => None,
+    })
+}
+
+/// Sets (or with `None` removes) the value at `keys`, creating missing parent
+/// objects. Existing keys keep their position.
+fn set_json_path(root: &mut JsonValue, keys: &[String], value: Option<JsonValue>) -> Result<(), String> {
+    let (last, parents) = keys.split_last().ok_or("Path must name a key")?;
+    let mut node = root;
+    for key in parents {
+        node = match node {
+            JsonValue::Object(map) => {
+                if !map.contains_key(key) {
+                    if value.is_none() {
+                        return Ok(());
+                    }
+                    map.insert(key.clone(), JsonValue::Object(Default::default()));
+                }
+                map.get_mut(key).unwrap()
+            }
+            JsonValue::Array(items) => {
+                let index = array_index(key, items.len())?;
+                match items.get_mut(index) {
+                    Some(item) => item,
+                    None => return Err(format!("Index {} is out of bounds", key)),
+                }
+            }
+            _ => return Err(format!("Cannot look up \"{}\" in a scalar value", key)),
+        };
+    }
+
+    match node {
+        JsonValue::Object(map) => match value {
+            Some(value) => {
+                map.insert(last.clone(), value);
+            }
+            None => {
+                map.shift_remove(last);
+            }
+        },
+        JsonValue::Array(items) => {
+            let index = array_index(last, items.len())?;
+            match value {
+                Some(value) if index == items.len() => items.push(value),
+                Some(value) => items[index] = value,
+                None if index < items.len() => {
+                    items.remove(index);
+                }
+                None => {}
+            }
+        }
+        _ => return Err(format!("Cannot set \"{}\" on a scalar value", last)),
+    }
+    Ok(())
+}
+
+/// Re-serializes JSON with the file's existing indentation and line endings.
+fn render_json(value: &JsonValue, original: &str) -> Result<String, String> {
+    let indent = original
+        .lines()
+        .skip(1)
+        .find_map(|line| {
+            let trimmed = line.trim_start();
+            (!trimmed.is_empty() && trimmed.len() < line.len()).then(|| &line[..line.len() - trimmed.len()])
+        })
+        .unwrap_or("  ");
+
+    let mut out = Vec::new();
+    let formatter = serde_json::ser::PrettyFormatter::with_indent(indent.as_bytes());
+    let mut serializer = serde_json::Serializer::with_formatter(&mut out, formatter);
+    value.serialize(&mut serializer).map_err(|e| e.to_string())?;
+
+    let mut content = String::from_utf8(out).map_err(|e| e.to_string())?;
+    content.push('\n');
+    if original.contains("\r\n") {
+        content = content.replace('\n', "\r\n");
+    }
+    Ok(content)
+}
+
+fn set_yaml_path(root: &mut YamlValue, keys: &[String], value: Option<YamlValue>) -> Result<(), String> {
+    let (last, parents) = keys.split_last().ok_or("Path must name a key")?;
+    if root.is_null() {
+        *root = YamlValue::Mapping(Default::default());
+    }
+    let mut node = root;
+    for key in parents {
+        node = match node {
+            YamlValue::Mapping(map) => {
+                if !map.contains_key(key.as_str()) {
+                    if value.is_none() {
+                        return Ok(());
+                    }
+                    map.insert(YamlValue::from(key.as_str()), YamlValue::Mapping(Default::default()));
+                }
+                map.get_mut(key.as_str()).unwrap()
+            }
+            YamlValue::Sequence(items) => {
+                let index = array_index(key, items.len())?;
+                match items.get_mut(index) {
+                    Some(item) => item,
+                    None => return Err(format!("Index {} is out of bounds", key)),
+                }
+            }
+            _ => return Err(format!("Cannot look up \"{}\" in a scalar value", key)),
+        };
+    }
+
+    match node {
+        YamlValue::Mapping(map) => match value {
+            Some(value) => {
+                map.insert(YamlValue::from(last.as_str()), value);
+            }
+            None => {
+                map.shift_remove(last.as_str());
+            }
+        },
+        YamlValue::Sequence(items) => {
+            let index = array_index(last, items.len())?;
+            match value {
+                Some(value) if index == items.len() => items.push(value),
+                Some(value) => items[index] = value,
+                None if index < items.len() => {
+                    items.remove(index);
+                }
+                None => {}
+            }
+        }
+        _ => return Err(format!("Cannot set \"{}\" on a scalar value", last)),
+    }
+    Ok(())
+}
+
+/// Leading spaces of a line that carries content, or None for blank and comment lines.
+fn yaml_indent(line: &str) -> Option<usize> {
+    let trimmed = line.trim_start_matches(' ');
+    let trimmed_end = trimmed.trim_end();
+    if trimmed_end.is_empty() || trimmed_end.starts_with('#') {
+        return None;
+    }
+    Some(line.len() - trimmed.len())
+}
+
+/// End of a scalar that starts at the beginning of `text`, excluding any
+/// trailing comment and whitespace.
+fn yaml_scalar_end(text: &str) -> Option<usize> {
+    let bytes = text.as_bytes();
+    match bytes.first()? {
+        b'"' => {
+            let mut i = 1;
+            while i < bytes.len() {
+                match bytes[i] {
+                    b'\\' => i += 2,
+                    b'"' => return Some(i + 1),
+                    _ => i += 1,
+                }
+            }
+            None
+        }
+        b'\'' => {
+            let mut i = 1;
+            while i < bytes.len() {
+                if bytes[i] == b'\'' {
+                    if bytes.get(i + 1) == Some(&b'\'') {
+                        i += 2;
+                        continue;
+                    }
+                    return Some(i + 1);
+                }
+                i += 1;
+            }
+            None
+        }
+        // Block scalars, anchors, aliases, tags and flow collections need a real parser
+        b'|' | b'>' | b'&' | b'*' | b'!' | b'{' | b'[' => None,
+        _ => {
+            let end = text.find(" #").unwrap_or(text.len());
+            Some(text[..end].trim_end().len())
+        }
+    }
+}
+
+/// Whether a YAML file has comments that re-serializing it would drop. A `#`
+/// inside a quoted string also counts, which errs on the side of refusing.
+fn yaml_has_comments(content: &str) -> bool {
+    content.lines().any(|line| {
+        let trimmed = line.trim_start();
+        trimmed.starts_with('#') || trimmed.contains(" #") || trimmed.contains("\t#")
+    })
+}
+
+/// Rewrites an existing scalar in a block-style mapping in place, so comments
+/// and formatting elsewhere in the file survive. Returns None whenever the
+/// layout is anything less simple; the caller then re-serializes instead, which
+/// is only allowed when the file has no comments to lose.
+fn yaml_replace_scalar(content: &str, keys: &[String], value: &JsonValue) -> Option<String> {
+    if value.is_null() || value.is_array() || value.is_object() {
+        return None;
+    }
+    let rendered = serde_yaml::to_string(value).ok()?;
+    let rendered = rendered.trim_end();
+    if rendered.contains('\n') {
+        return None;
+    }
+
+    let lines: Vec<&str> = content.split_inclusive('\n').collect();
+    let (mut start, mut end) = (0, lines.len());
+    for (depth, key) in keys.iter().enumerate() {
+        // Keys of this mapping sit at the indent of its first entry
+        let indent = (start..end).find_map(|i| yaml_indent(lines[i]))?;
+        let (index, after_key) = (start..end).find_map(|i| {
+            if yaml_indent(lines[i]) != Some(indent) {
+                return None;
+            }
+            let rest = &lines[i][indent..];
+            [key.clone(), format!("\"{}\"", key), format!("'{}'", key)]
+                .iter()
+                .find_map(|candidate| rest.strip_prefix(candidate.as_str())?.strip_prefix(':'))
+                .filter(|after| after.is_empty() || after.starts_with([' ', '\t', '\r', '\n']))
+                .map(|after| (i, lines[i].len() - after.len()))
+        })?;
+
+        let block_end = (index + 1..end)
+            .find(|&i| yaml_indent(lines[i]).is_some_and(|n| n <= indent))
+            .unwrap_or(end);
+        let line = lines[index];
+        let value_text = line[after_key..].trim_start_matches([' ', '\t']);
+        let value_start = line.len() - value_text.len();
+
+        if depth + 1 < keys.len() {
+            // Parents must open a nested block mapping on the following lines
+            if !(value_text.trim().is_empty() || value_text.starts_with('#')) {
+                return None;
+            }
+            (start, end) = (index + 1, block_end);
+            continue;
+        }
+
+        // A plain scalar continued on more-indented lines isn't safe to splice
+        if block_end > index + 1 && (index + 1..block_end).any(|i| yaml_indent(lines[i]).is_some()) {
+            return None;
+        }
+        let value_end = value_start + yaml_scalar_end(value_text.trim_end_matches(['\r', '\n']))?;
+        if value_end == value_start {
+            return None;
+        }
+
+        let mut edited = String::with_capacity(content.len() + rendered.len());
+        lines[..index].iter().for_each(|l| edited.push_str(l));
+        edited.push_str(&line[..value_start]);
+        edited.push_str(rendered);
+        edited.push_str(&line[value_end..]);
+        lines[index + 1..].iter().for_each(|l| edited.push_str(l));
+        return Some(edited);
+    }
+    None
+}
+
+enum TomlNode<'a> {
+    Table(&'a mut Table),
+    Value(&'a mut toml_edit::Value),
+    Tables(&'a mut toml_edit::ArrayOfTables),
+}
+
+fn toml_node(item: &mut Item) -> Option<TomlNode<'_>> {
+    match item {
+        Item::None => None,
+        Item::Value(value) => Some(TomlNode::Value(value)),
+        Item::Table(table) => Some(TomlNode::Table(table)),
+        Item::ArrayOfTables(tables) => Some(TomlNode::Tables(tables)),
+    }
+}
+
+/// Descends one key, creating a missing table when `create` is set.
+fn toml_child<'a>(node: TomlNode<'a>, key: &str, create: bool) -> Result<Option<TomlNode<'a>>, String> {
+    match node {
+        TomlNode::Table(table) => {
+            if !table.contains_key(key) {
+                if !create {
+                    return Ok(None);
+                }
+                let mut child = Table::new();
+                child.set_implicit(true);
+                table.insert(key, Item::Table(child));
+            }
+            Ok(table.get_mut(key).and_then(toml_node))
+        }
+        TomlNode::Value(toml_edit::Value::InlineTable(table)) => {
+            if !table.contains_key(key) {
+                if !create {
+                    return Ok(None);
+                }
+                table.insert(key, toml_edit::Value::InlineTable(InlineTable::new()));
+            }
+            Ok(table.get_mut(key).map(TomlNode::Value))
+        }
+        TomlNode::Value(toml_edit::Value::Array(items)) => {
+            let index = array_index(key, items.len())?;
+            Ok(items.get_mut(index).map(TomlNode::Value))
+        }
+        TomlNode::Tables(tables) => {
+            let index = array_index(key, tables.len())?;
+            Ok(tables.get_mut(index).map(TomlNode::Table))
+        }
+        TomlNode::Value(_) => Err(format!("Cannot look up \"{}\" in a scalar value", key)),
+    }
+}
+
+/// Sets (or with `None` removes) `key` under `node`. Replaced values keep their
+/// surrounding whitespace and trailing comments.
+fn toml_assign(node: TomlNode<'_>, key: &str, value: Option<&JsonValue>) -> Result<(), String> {
+    let value = value.map(json_to_toml).transpose()?;
+    match node {
+        TomlNode::Table(table) => match (value, table.get_mut(key)) {
+            (None, _) => {
+                table.remove(key);
+            }
+            (Some(mut value), Some(Item::Value(old))) => {
+                *value.decor_mut() = old.decor().clone();
+                *old = value;
+            }
+            // Objects replacing a [table] stay a [table] rather than turning inline
+            (Some(toml_edit::Value::InlineTable(inline)), Some(Item::Table(old))) => {
+                let mut table = inline.into_table();
+                *table.decor_mut() = old.decor().clone();
+                *old = table;
+            }
+            (Some(value), _) => {
+                table.insert(key, Item::Value(value));
+            }
+        },
+        TomlNode::Value(toml_edit::Value::InlineTable(table)) => match (value, table.get_mut(key)) {
+            (None, _) => {
+                table.remove(key);
+            }
+            (Some(mut value), Some(old)) => {
+                *value.decor_mut() = old.decor().clone();
+                *old = value;
+            }
+            (Some(value), None) => {
+                table.insert(key, value);
+            }
+        },
+        TomlNode::Value(toml_edit::Value::Array(items)) => {
+            let index = array_index(key, items.len())?;
+            match value {
+                Some(value) if index == items.len() => items.push(value),
+                Some(value) => {
+                    items.replace(index, value);
+                }
+                None if index < items.len() => {
+                    items.remove(index);
+                }
+                None => {}
+            }
+        }
+        TomlNode::Tables(tables) => {
+            let index = array_index(key, tables.len())?;
+            match value {
+                Some(toml_edit::Value::InlineTable(inline)) => {
+                    let table = inline.into_table();
+                    match tables.get_mut(index) {
+                        Some(old) => *old = table,
+                        None => tables.push(table),
+                    }
+                }
+                Some(_) => return Err("Entries of an array of tables must be objects".to_string()),
+                None if index < tables.len() => {
+                    tables.remove(index);
+                }
+                None => {}
+            }
+        }
+        TomlNode::Value(_) => return Err(format!("Cannot set \"{}\" on a scalar value", key)),
+    }
+    Ok(())
+}
+
+fn set_toml_path(doc: &mut DocumentMut, keys: &[String], value: Option<&JsonValue>) -> Result<(), String> {
+    let (last, parents) = keys.split_last().ok_or("Path must name a key")?;
+    let mut node = TomlNode::Table(doc.as_table_mut());
+    for key in parents {
+        node = match toml_child(node, key, value.is_some())? {
+            Some(child) => child,
+            // Removing under a missing parent is a no-op
+            None if value.is_none() => return Ok(()),
+            None => return Err(format!("Index {} is out of bounds", key)),
+        };
+    }
+    toml_assign(node, last, value)
+}
+
+/// Applies a single-key edit to `content`, changing as little of the file as
+/// the format allows.
+pub fn edit_config_content(
+    format: ConfigFormat,
+    content: &str,
+    keys: &[String],
+    value: Option<JsonValue>,
+) -> Result<String, ConfigFileError> {
+    match parse_config(format, content)? {
+        ConfigDocument::Json(mut root) => {
+            set_json_path(&mut root, keys, value)?;
+            Ok(render_json(&root, content)?)
+        }
+        ConfigDocument::Yaml(mut root) => {
+            let yaml_value = value
+                .as_ref()
+                .map(serde_yaml::to_value)
+                .transpose()
+                .map_err(|e| e.to_string())?;
+            set_yaml_path(&mut root, keys, yaml_value)?;
+
+            // Only keep the in-place edit if it parses back to exactly the edited tree
+            let in_place = value
+                .as_ref()
+                .and_then(|value| yaml_replace_scalar(content, keys, value))
+                .filter(|edited| serde_yaml::from_str::<YamlValue>(edited).is_ok_and(|parsed| parsed == root));
+            match in_place {
+                Some(edited) => Ok(edited),
+                None if yaml_has_comments(content) => Err(ConfigFileError::from(
+                    "This change can't be made in place, and rewriting the YAML file would drop its comments. Edit the file directly instead.".to_string(),
+                )),
+                None => Ok(serde_yaml::to_string(&root).map_err(|e| e.to_string())?),
+            }
+        }
+        ConfigDocument::Toml(mut doc) => {
+            set_toml_path(&mut doc, keys, value.as_ref())?;
+            Ok(doc.to_string())
+        }
+    }
+}
+
+/// Checks `content` parses as the file's format without saving it.
+#[tauri::command]
+pub fn validate_config_file(
+    path: String,
+    content: String,
+    format: Option<ConfigFormat>,
+) -> Result<(), ConfigFileError> {
+    let format = ConfigFormat::resolve(format, &expand_home(&path)?)?;
+    parse_config(format, &content).map(|_| ())
+}
+
+/// Reads one value by JSON-pointer-like path; None if the file or key is missing.
+#[tauri::command]
+pub fn get_config_value(
+    path: String,
+    key: String,
+    format: Option<ConfigFormat>,
+) -> Result<Option<JsonValue>, ConfigFileError> {
+    let p = expand_home(&path)?;
+    let format = ConfigFormat::resolve(format, &p)?;
+    if !p.exists() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(&p).map_err(|e| e.to_string())?;
+    let root = parse_config(format, &content)?.to_json()?;
+    Ok(get_json_path(&root, &parse_config_path(&key)).cloned())
+}
+
+/// Sets one value by JSON-pointer-like path, leaving the rest of the file as
+/// intact as the format allows. A null value removes the key.
+#[tauri::command]
+pub fn set_config_value(
+    app: AppHandle,
+    path: String,
+    key: String,
+    value: Option<JsonValue>,
+    format: Option<ConfigFormat>,
+) -> Result<(), ConfigFileError> {
+    let p = expand_home(&path)?;
+    let format = ConfigFormat::resolve(format, &p)?;
+    let content = if p.exists() {
+        fs::read_to_string(&p).map_err(|e| e.to_string())?
+    } else {
+        String::new()
+    };
+    let edited = edit_config_content(format, &content, &parse_config_path(&key), value)?;
+    Ok(write_config_file(&app, &p, &edited)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edit(format: ConfigFormat, content: &str, path: &str, value: Option<JsonValue>) -> Result<String, ConfigFileError> {
+        edit_config_content(format, content, &parse_config_path(path), value)
+    }
+
+    #[test]
+    fn json_edit_keeps_key_order_and_indentation() {
+        let content = "{\n    \"zeta\": 1,\n    \"alpha\": {\n        \"b\": true\n    }\n}\n";
+        let edited = edit(ConfigFormat::Json, content, "/zeta", Some(serde_json::json!(2))).unwrap();
+        assert_eq!(edited, "{\n    \"zeta\": 2,\n    \"alpha\": {\n        \"b\": true\n    }\n}\n");
+
+        let added = edit(ConfigFormat::Json, content, "/alpha/a", Some(serde_json::json!("x"))).unwrap();
+        assert_eq!(
+            added,
+            "{\n    \"zeta\": 1,\n    \"alpha\": {\n        \"b\": true,\n        \"a\": \"x\"\n    }\n}\n"
+        );
+    }
+
+    #[test]
+    fn json_edit_keeps_crlf() {
+        let content = "{\r\n\t\"a\": 1,\r\n\t\"b\": 2\r\n}\r\n";
+        let edited = edit(ConfigFormat::Json, content, "/a", None).unwrap();
+        assert_eq!(edited, "{\r\n\t\"b\": 2\r\n}\r\n");
+    }
+
+    #[test]
+    fn path_escapes_and_array_append() {
+        assert_eq!(parse_config_path("/a~1b/c~0d/~01"), vec!["a/b", "c~d", "~1"]);
+
+        let content = "{\n  \"servers\": {\n    \"a/b\": [1]\n  }\n}\n";
+        let edited = edit(ConfigFormat::Json, content, "/servers/a~1b/-", Some(serde_json::json!(2))).unwrap();
+        let root: JsonValue = serde_json::from_str(&edited).unwrap();
+        assert_eq!(root["servers"]["a/b"], serde_json::json!([1, 2]));
+
+        let err = edit(ConfigFormat::Json, content, "/servers/a~1b/5", Some(serde_json::json!(2))).unwrap_err();
+        assert!(err.message.contains("out of bounds"), "{}", err);
+    }
+
+    #[test]
+    fn toml_replace_keeps_comments_and_table_decor() {
+        let content = "# top comment\n\n[model]   # model settings\nname = \"old\" # trailing\nother = 1\n";
+        let edited = edit(ConfigFormat::Toml, content, "/model/name", Some(serde_json::json!("new"))).unwrap();
+        assert_eq!(
+            edited,
+            "# top comment\n\n[model]   # model settings\nname = \"new\" # trailing\nother = 1\n"
+        );
+    }
+
+    #[test]
+    fn toml_rejects_integers_past_i64() {
+        let err = edit(ConfigFormat::Toml, "", "/big", Some(serde_json::json!(u64::MAX))).unwrap_err();
+        assert!(err.message.contains("too large"), "{}", err);
+        let ok = edit(ConfigFormat::Toml, "", "/big", Some(serde_json::json!(i64::MAX))).unwrap();
+        assert_eq!(ok, format!("big = {}\n", i64::MAX));
+    }
+
+    #[test]
+    fn yaml_scalar_edit_in_place_keeps_comments() {
+        let content = "# settings\nmodel:\n  name: old  # pinned\n  temperature: 0.2\nother: true\n";
+        let edited = edit(ConfigFormat::Yaml, content, "/model/name", Some(serde_json::json!("new"))).unwrap();
+        assert_eq!(edited, "# settings\nmodel:\n  name: new  # pinned\n  temperature: 0.2\nother: true\n");
+    }
+
+    #[test]
+    fn yaml_refuses_edits_that_would_drop_comments() {
+        let content = "# settings\nmodel:\n  name: old\n";
+        let err = edit(ConfigFormat::Yaml, content, "/model/added", Some(serde_json::json!(1))).unwrap_err();
+        assert!(err.message.contains("comments"), "{}", err);
+
+        // Without comments there is nothing to lose, so re-serializing is fine
+        let edited = edit(ConfigFormat::Yaml, "model:\n  name: old\n", "/model/added", Some(serde_json::json!(1))).unwrap();
+        assert_eq!(edited, "model:\n  name: old\n  added: 1\n");
+    }
+
+    #[test]
+    fn parse_errors_report_line_and_column() {
+        let json = parse_config(ConfigFormat::Json, "{\n  \"a\": 1,\n  \"b\" 2\n}").err().unwrap();
+        assert_eq!((json.line, json.column), (Some(3), Some(7)));
+
+        let toml = parse_config(ConfigFormat::Toml, "a = 1\nb = \n").err().unwrap();
+        assert_eq!(toml.line, Some(2));
+        assert!(toml.column.is_some());
+
+        let yaml = parse_config(ConfigFormat::Yaml, "a: 1\nb: [1, 2\n").err().unwrap();
+        assert!(yaml.line.is_some() && yaml.column.is_some(), "{}", yaml);
+        assert!(!yaml.message.contains(" at line "), "{}", yaml.message);
+        assert!(yaml.message.contains("while parsing"), "{}", yaml.message);
+    }
+}
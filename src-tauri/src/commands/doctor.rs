@@ -0,0 +1,336 @@
+#![allow(unused_imports)]
+#![allow(dead_code)]
+
+use futures_util::StreamExt;
+use portable_pty::{Child, CommandBuilder, MasterPty, NativePtySystem, PtySize, PtySystem};
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::cmp::min;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+#[cfg(windows)]
+use std::os::windows::process::CommandExt;
+
+use crate::commands::pty::*;
+use crate::commands::probes::*;
+use crate::commands::registry::*;
+use crate::commands::env::*;
+use crate::commands::config::*;
+use crate::commands::fs::*;
+use crate::commands::chat::*;
+use crate::commands::shell_env::*;
+use crate::commands::skills::*;
+use crate::commands::utils::*;
+
+/// Host used to check that the configured proxy (or a direct connection) works.
+const REACHABILITY_URL: &str = "https://registry.npmjs.org/";
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DoctorInstall {
+    pub path: String,
+    pub version: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DoctorTool {
+    pub id: String,
+    pub program: String,
+    pub npm_package: Option<String>,
+    /// Every install found on PATH, in PATH order; the first one is what runs
+    pub installs: Vec<DoctorInstall>,
+    /// PATH entries that contain the program
+    pub path_entries: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DoctorNpm {
+    pub prefix: Option<String>,
+    /// Where npm puts global executables
+    pub bin_dir: Option<String>,
+    pub bin_dir_on_path: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DoctorProxy {
+    pub proxy: Option<String>,
+    pub url: String,
+    pub reachable: bool,
+    pub status: Option<u16>,
+    pub latency_ms: Option<u64>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DoctorIssue {
+    /// "error" or "warning"
+    pub severity: String,
+    pub tool_id: Option<String>,
+    pub message: String,
+    pub fix: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DoctorReport {
+    pub path: Vec<String>,
+    pub node: ProbeResult,
+    pub npm: DoctorNpm,
+    pub proxy: DoctorProxy,
+    pub tools: Vec<DoctorTool>,
+    pub issues: Vec<DoctorIssue>,
+}
+
+impl DoctorIssue {
+    fn new(severity: &str, tool_id: Option<&str>, message: String, fix: Option<String>) -> Self {
+        DoctorIssue {
+            severity: severity.to_string(),
+            tool_id: tool_id.map(|s| s.to_string()),
+            message,
+            fix,
+        }
+    }
+}
+
+fn path_entries() -> Vec<PathBuf> {
+    shell_path()
+        .map(|p| std::env::split_paths(&p).collect())
+        .unwrap_or_default()
+}
+
+fn same_dir(a: &std::path::Path, b: &std::path::Path) -> bool {
+    match (fs::canonicalize(a), fs::canonicalize(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+/// All copies of a program on PATH. Links that resolve to a file already seen
+/// count once, so a symlinked shim doesn't look like a second install.
+fn find_installs(tool: &ToolDefinition) -> DoctorTool {
+    let args: Vec<&str> = tool.version_args.iter().map(|s| s.as_str()).collect();
+    let mut seen: Vec<PathBuf> = Vec::new();
+    let mut installs = Vec::new();
+    let mut dirs = Vec::new();
+
+    for path in find_all_programs(&tool.program) {
+        if let Some(dir) = path.parent() {
+            let dir = dir.to_string_lossy().to_string();
+            if !dirs.contains(&dir) {
+                dirs.push(dir);
+            }
+        }
+        let resolved = fs::canonicalize(&path).unwrap_or_else(|_| path.clone());
+        if seen.contains(&resolved) {
+            continue;
+        }
+        seen.push(resolved);
+        installs.push(DoctorInstall {
+            version: probe_version(&path.to_string_lossy(), &args, PROBE_TIMEOUT).version,
+            path: path.to_string_lossy().to_string(),
+        });
+    }
+
+    DoctorTool {
+        id: tool.id.clone(),
+        program: tool.program.clone(),
+        npm_package: tool.npm_package.clone(),
+        installs,
+        path_entries: dirs,
+    }
+}
+
+fn check_npm(path: &[PathBuf]) -> DoctorNpm {
+    let prefix = probe_version("npm", &["prefix", "-g"], PROBE_TIMEOUT).version;
+    // npm links global executables into the prefix itself on Windows and prefix/bin elsewhere
+    let bin_dir = prefix.as_ref().map(|p| {
+        if cfg!(target_os = "windows") {
+            PathBuf::from(p)
+        } else {
+            PathBuf::from(p).join("bin")
+        }
+    });
+    let bin_dir_on_path = bin_dir
+        .as_ref()
+        .map(|bin| path.iter().any(|p| same_dir(p, bin)))
+        .unwrap_or(false);
+    DoctorNpm {
+        prefix,
+        bin_dir: bin_dir.map(|b| b.to_string_lossy().to_string()),
+        bin_dir_on_path,
+    }
+}
+
+async fn check_proxy(app: &AppHandle) -> DoctorProxy {
+    let config = get_app_config(app.clone());
+    let proxy = config
+        .proxy_address
+        .filter(|a| !a.is_empty() && config.proxy_type.as_deref().unwrap_or("none") != "none");
+    let mut report = DoctorProxy {
+        proxy,
+        url: REACHABILITY_URL.to_string(),
+        reachable: false,
+        status: None,
+        latency_ms: None,
+        error: None,
+    };
+
+    let client = match get_proxy_client(app) {
+        Ok(client) => client,
+        Err(e) => {
+            report.error = Some(e);
+            return report;
+        }
+    };
+    let started = std::time::Instant::now();
+    match client
+        .head(REACHABILITY_URL)
+        .timeout(std::time::Duration::from_secs(10))
+        .send()
+        .await
+    {
+        Ok(res) => {
+            report.status = Some(res.status().as_u16());
+            report.latency_ms = Some(started.elapsed().as_millis() as u64);
+            // Any HTTP answer means the network path works, even if the endpoint rejects HEAD
+            report.reachable = true;
+        }
+        Err(e) => report.error = Some(e.to_string()),
+    }
+    report
+}
+
+fn diagnose(report: &mut DoctorReport, recorded_methods: &HashMap<String, String>) {
+    let mut issues = Vec::new();
+    let node_found = report.node.status == ProbeStatus::Found;
+
+    if report.npm.prefix.is_some() && !report.npm.bin_dir_on_path {
+        let bin = report.npm.bin_dir.clone().unwrap_or_default();
+        issues.push(DoctorIssue::new(
+            "warning",
+            None,
+            format!("npm's global bin directory {} is not on PATH", bin),
+            Some(if cfg!(target_os = "windows") {
+                format!("Add {} to the user PATH in System Properties > Environment Variables", bin)
+            } else {
+                format!("Add `export PATH=\"{}:$PATH\"` to your shell profile", bin)
+            }),
+        ));
+    }
+
+    for tool in &report.tools {
+        let via_npm = tool.npm_package.is_some()
+            && recorded_methods.get(&tool.id).map(|m| m == "npm").unwrap_or(true);
+
+        if tool.installs.len() > 1 {
+            let active = &tool.installs[0];
+            let shadowed: Vec<&str> = tool.installs[1..].iter().map(|i| i.path.as_str()).collect();
+            issues.push(DoctorIssue::new(
+                "warning",
+                Some(&tool.id),
+                format!(
+                    "{} is installed {} times; {} shadows {}",
+                    tool.program,
+                    tool.installs.len(),
+                    active.path,
+                    shadowed.join(", ")
+                ),
+                Some("Uninstall the copies you don't use, or reorder PATH so the intended one comes first".to_string()),
+            ));
+        }
+
+        if tool.installs.is_empty() && tool.npm_package.is_some() && via_npm {
+            if let Some(bin) = &report.npm.bin_dir {
+                let shim = PathBuf::from(bin).join(&tool.program);
+                let exists = shim.exists() || shim.with_extension("cmd").exists();
+                if exists && !report.npm.bin_dir_on_path {
+                    issues.push(DoctorIssue::new(
+                        "error",
+                        Some(&tool.id),
+                        format!("{} is installed in {} but that directory is not on PATH", tool.program, bin),
+                        Some(format!("Add {} to PATH and restart the app", bin)),
+                    ));
+                }
+            }
+        }
+
+        if !tool.installs.is_empty() && tool.npm_package.is_some() && via_npm && !node_found {
+            issues.push(DoctorIssue::new(
+                "error",
+                Some(&tool.id),
+                format!("{} is an npm package but Node.js was not found", tool.program),
+                Some("Install Node.js LTS from https://nodejs.org or through nvm/fnm/volta".to_string()),
+            ));
+        }
+    }
+
+    if !report.proxy.reachable {
+        let fix = if report.proxy.proxy.is_some() {
+            "Check the proxy address in Settings, or switch the proxy off"
+        } else {
+            "Check your network connection, or configure a proxy in Settings"
+        };
+        issues.push(DoctorIssue::new(
+            "error",
+            None,
+            format!(
+                "Cannot reach {}: {}",
+                report.proxy.url,
+                report.proxy.error.clone().unwrap_or_default()
+            ),
+            Some(fix.to_string()),
+        ));
+    }
+
+    report.issues = issues;
+}
+
+#[tauri::command]
+pub async fn run_environment_doctor(app: AppHandle) -> Result<DoctorReport, String> {
+    let registry = ToolRegistry::load(&app);
+    let (path, node, npm, tools) = tauri::async_runtime::spawn_blocking(move || {
+        let path = path_entries();
+        thread::scope(|scope| {
+            let node = scope.spawn(|| probe_candidates(&["node"], &["-v"], PROBE_TIMEOUT));
+            let npm = scope.spawn(|| check_npm(&path));
+            let tools: Vec<_> = registry
+                .tools
+                .iter()
+                .map(|tool| scope.spawn(move || find_installs(tool)))
+                .collect();
+
+            let node = node.join().unwrap_or_default();
+            let npm = npm.join().map_err(|_| "npm check panicked".to_string())?;
+            let tools = tools
+                .into_iter()
+                .filter_map(|t| t.join().ok())
+                .collect::<Vec<_>>();
+            Ok::<_, String>((path.clone(), node, npm, tools))
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+
+    let proxy = check_proxy(&app).await;
+    let recorded_methods: HashMap<String, String> = get_app_config(app.clone())
+        .tool_statuses
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|(id, s)| s.install_method.map(|m| (id, m)))
+        .collect();
+
+    let mut report = DoctorReport {
+        path: path.iter().map(|p| p.to_string_lossy().to_string()).collect(),
+        node,
+        npm,
+        proxy,
+        tools,
+        issues: Vec::new(),
+    };
+    diagnose(&mut report, &recorded_methods);
+    Ok(report)
+}
@@ -22,23 +22,33 @@ use crate::commands::pty::*;
 use crate::commands::config::*;
 use crate::commands::fs::*;
 use crate::commands::chat::*;
+use crate::commands::installer::*;
+use crate::commands::node::*;
+use crate::commands::probes::*;
+use crate::commands::registry::*;
+use crate::commands::secrets::*;
+use crate::commands::shell_env::*;
 use crate::commands::skills::*;
 use crate::commands::utils::*;
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
 pub struct EnvironmentStatus {
-    node_version: Option<String>,
-    npm_version: Option<String>,
-    git_version: Option<String>,
-    python_version: Option<String>,
-    go_version: Option<String>,
-    java_version: Option<String>,
+    // Fixed fields kept for older renderers; mirror the probes of the same name
+    node_version: ProbeResult,
+    npm_version: ProbeResult,
+    git_version: ProbeResult,
+    python_version: ProbeResult,
+    go_version: ProbeResult,
+    java_version: ProbeResult,
     // AI Tools
-    gh_version: Option<String>,
-    claude_version: Option<String>,
-    opencode_version: Option<String>,
-    qoder_version: Option<String>,
-    codebuddy_version: Option<String>,
+    gh_version: ProbeResult,
+    claude_version: ProbeResult,
+    opencode_version: ProbeResult,
+    qoder_version: ProbeResult,
+    codebuddy_version: ProbeResult,
+    // Every probe by name, including ones added in config
+    probes: HashMap<String, ProbeResult>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -46,6 +56,12 @@ pub struct ToolStatus {
     id: String,
     installed: bool,
     version: Option<String>,
+    // Set by the backend when it installed the tool, e.g. "npm", "brew", "archive"
+    pub install_method: Option<String>,
+    // Version that was installed before the last install/update, for rollback
+    pub previous_version: Option<String>,
+    // NodeInstall id the CLI lives under, e.g. "nvm@20.11.0"; None if not a Node tool
+    pub node_install: Option<String>,
 }
 
 #[tauri::command]
@@ -57,54 +73,59 @@ pub fn check_executable(program: String, args: Vec<String>) -> Option<String> {
 }
 
 #[tauri::command]
-pub fn check_environment() -> EnvironmentStatus {
-    EnvironmentStatus {
-        node_version: get_version("node", &["-v"]),
-        npm_version: get_version("npm", &["-v"]),
-        git_version: get_version("git", &["--version"]),
-        python_version: get_version("python", &["--version"])
-            .or_else(|| get_version("python3", &["--version"])),
-        go_version: get_version("go", &["version"]),
-        java_version: get_version("java", &["-version"]),
-        gh_version: get_version("gh", &["--version"]),
-        claude_version: get_version("claude", &["--version"]),
-        opencode_version: get_version("opencode", &["--version"]),
-        qoder_version: get_version("qoder", &["--version"]),
-        codebuddy_version: get_version("codebuddy", &["--version"]),
-    }
+pub async fn check_environment(app: AppHandle) -> EnvironmentStatus {
+    let probes = load_probes(&app);
+    tauri::async_runtime::spawn_blocking(move || {
+        let probes = run_probes(&probes);
+        let legacy = |name: &str| probes.get(name).cloned().unwrap_or_default();
+        EnvironmentStatus {
+            node_version: legacy("node"),
+            npm_version: legacy("npm"),
+            git_version: legacy("git"),
+            python_version: legacy("python"),
+            go_version: legacy("go"),
+            java_version: legacy("java"),
+            gh_version: legacy("gh"),
+            claude_version: legacy("claude"),
+            opencode_version: legacy("opencode"),
+            qoder_version: legacy("qoder"),
+            codebuddy_version: legacy("codebuddy"),
+            probes,
+        }
+    })
+    .await
+    .unwrap_or_default()
 }
 
 #[tauri::command]
-pub fn launch_tool(tool_id: String) -> Result<String, String> {
-    launch_tool_with_args(tool_id, None)
+pub fn launch_tool(app: AppHandle, tool_id: String) -> Result<String, String> {
+    launch_tool_with_args(app, tool_id, None)
 }
 
 #[tauri::command]
-pub fn launch_tool_with_args(tool_id: String, args: Option<Vec<String>>) -> Result<String, String> {
-    let program = match tool_id.as_str() {
-        "claude" => "claude",
-        "google" => "gemini",
-        "opencode" => "opencode",
-        "qoder" => "qodercli",
-        "codebuddy" => "codebuddy",
-        "copilot" => "copilot",
-        "codex" => "codex",
-        "open_in_ide" => {
-            if let Some(ref a) = args {
-                if a.len() >= 2 {
-                    let ide_path = &a[0];
-                    let file_path = &a[1];
-                    Command::new(ide_path)
-                        .arg(file_path)
-                        .spawn()
-                        .map_err(|e| e.to_string())?;
-                    return Ok(format!("Opened {} in IDE", file_path));
-                }
+pub fn launch_tool_with_args(app: AppHandle, tool_id: String, args: Option<Vec<String>>) -> Result<String, String> {
+    if tool_id == "open_in_ide" {
+        if let Some(ref a) = args {
+            if a.len() >= 2 {
+                let ide_path = &a[0];
+                let file_path = &a[1];
+                Command::new(ide_path)
+                    .arg(file_path)
+                    .spawn()
+                    .map_err(|e| e.to_string())?;
+                return Ok(format!("Opened {} in IDE", file_path));
             }
-            return Err("Invalid arguments for open_in_ide".to_string());
         }
-        _ => return Err("Unknown tool".to_string()),
-    };
+        return Err("Invalid arguments for open_in_ide".to_string());
+    }
+
+    let registry = ToolRegistry::load(&app);
+    let tool = registry.get(&tool_id).ok_or("Unknown tool")?;
+    // Archive installs live in the managed bin dir, which isn't on the terminal's PATH
+    let program = resolve_tool_program(&app, tool);
+    let working_directory = get_tool_config(&app, tool)
+        .and_then(|c| c.working_directory)
+        .filter(|dir| std::path::Path::new(dir).is_dir());
 
     let mut full_args = Vec::new();
     if let Some(a) = args {
@@ -115,41 +136,60 @@ pub fn launch_tool_with_args(tool_id: String, args: Option<Vec<String>>) -> Resu
     #[cfg(target_os = "windows")]
     {
         // Use 'sh' (Git Bash) instead of powershell as requested by user
-        let mut full_cmd = program.to_string();
+        let mut full_cmd = sh_quote(&program);
         for arg in &full_args {
             full_cmd.push_str(" ");
-            full_cmd.push_str(arg);
+            full_cmd.push_str(&sh_quote(arg));
         }
         
         // Use 'cmd /C start sh -c ...' to open a new bash window
         // We add '|| read -p "Press Enter to close..." ' to keep it open if it fails or finishes fast
         let bash_cmd = format!("{} || read -n 1 -p 'Press any key to close...'", full_cmd);
         
-        Command::new("cmd")
-            .args(&["/C", "start", "sh", "-c", &bash_cmd])
+        let mut cmd = Command::new("cmd");
+        cmd.envs(tool_launch_env(&app, tool));
+        if let Some(dir) = &working_directory {
+            cmd.current_dir(dir);
+        }
+        cmd.args(&["/C", "start", "sh", "-c", &bash_cmd])
             .spawn()
             .map_err(|e| format!("Failed to launch Git Bash (sh): {}. Please ensure Git Bash is in your PATH.", e))?;
     }
 
+    // Launch Services starts Terminal.app without our environment or working
+    // directory, so both go into a script that Terminal runs
     #[cfg(target_os = "macos")]
     {
-        let mut final_cmd = program.to_string();
-        for arg in &full_args {
-            final_cmd.push_str(" ");
-            final_cmd.push_str(arg);
-        }
-        Command::new("open")
-            .args(&["-a", "Terminal", &final_cmd])
+        let script = terminal_launch_script(
+            working_directory.as_deref(),
+            &tool_launch_env(&app, tool),
+            &program,
+            &full_args,
+        );
+        let script_path = write_launch_script(&tool.id, &script)?;
+        // Run as a child of Terminal's shell so the window stays open afterwards
+        let run = sh_quote(&script_path.to_string_lossy()).replace('\\', "\\\\").replace('"', "\\\"");
+        Command::new("osascript")
+            .args([
+                "-e",
+                &format!("tell application \"Terminal\" to do script \"{}\"", run),
+                "-e",
+                "tell application \"Terminal\" to activate",
+            ])
             .spawn()
             .map_err(|e| e.to_string())?;
     }
 
     #[cfg(target_os = "linux")]
     {
-        let mut final_args = vec![program.to_string()];
+        let mut final_args = vec![program.clone()];
         final_args.extend(full_args.clone());
-        Command::new("x-terminal-emulator")
-            .args(&["-e"])
+        let mut cmd = Command::new("x-terminal-emulator");
+        cmd.envs(tool_launch_env(&app, tool));
+        if let Some(dir) = &working_directory {
+            cmd.current_dir(dir);
+        }
+        cmd.args(&["-e"])
             .args(&final_args)
             .spawn()
             .map_err(|e| e.to_string())?;
@@ -158,193 +198,831 @@ pub fn launch_tool_with_args(tool_id: String, args: Option<Vec<String>>) -> Resu
     Ok(format!("Launched {} with args {:?}", tool_id, full_args))
 }
 
-#[tauri::command]
-pub async fn install_tool(tool_id: String) -> Result<String, String> {
-    let install_package = match tool_id.as_str() {
-        "claude" => "@anthropic-ai/claude-code",
-        "google" => "@google/gemini-cli",
-        "opencode" => "opencode-ai",
-        "qoder" => "@qoder-ai/qodercli",
-        "codebuddy" => "@tencent-ai/codebuddy-code",
-        "copilot" => "@github/copilot",
-        "codex" => "@openai/codex",
-        _ => return Err("Installation not supported for this tool yet".to_string()),
-    };
+/// A POSIX shell script that sets up a tool's environment and working directory,
+/// then runs it. The script deletes itself first, since it may hold an API key.
+fn terminal_launch_script(
+    working_directory: Option<&str>,
+    env: &HashMap<String, String>,
+    program: &str,
+    args: &[String],
+) -> String {
+    let mut script = String::from("#!/bin/sh\nrm -f \"$0\"\n");
+    if let Some(dir) = working_directory {
+        script.push_str(&format!("cd {} || exit 1\n", sh_quote(dir)));
+    }
+    let mut names: Vec<&String> = env.keys().collect();
+    names.sort();
+    for name in names {
+        // Names come from tool manifests; anything else can't be exported safely
+        let valid = !name.is_empty()
+            && !name.starts_with(|c: char| c.is_ascii_digit())
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if valid {
+            script.push_str(&format!("export {}={}\n", name, sh_quote(&env[name])));
+        }
+    }
+    let mut command = sh_quote(program);
+    for arg in args {
+        command.push(' ');
+        command.push_str(&sh_quote(arg));
+    }
+    script.push_str(&command);
+    script.push('\n');
+    script
+}
 
-    // This is a simplified install command execution
-    // Ideally we stream output to frontend, here we just wait
-    // Note: This blocks the thread, should use async command in real app
-    // Determine the npm program and arguments based on OS
-    let (program, args) = if cfg!(target_os = "windows") {
-        ("npm.cmd", vec!["install", "-g", install_package])
+/// Writes an owner-only executable script to the temp dir.
+fn write_launch_script(tool_id: &str, script: &str) -> Result<PathBuf, String> {
+    let path = std::env::temp_dir().join(format!("{}.sh", new_job_id(&format!("openvizui-{}", tool_id))));
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o700);
+    }
+    let mut file = options.open(&path).map_err(|e| e.to_string())?;
+    file.write_all(script.as_bytes()).map_err(|e| e.to_string())?;
+    Ok(path)
+}
+
+/// Quotes an argument for a POSIX shell command string.
+fn sh_quote(arg: &str) -> String {
+    let safe = !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./=:@,+%".contains(c));
+    if safe {
+        arg.to_string()
     } else {
-        ("npm", vec!["install", "-g", install_package])
-    };
+        format!("'{}'", arg.replace('\'', "'\\''"))
+    }
+}
 
-    println!("Executing install command: {} {:?}", program, args);
+/// The user's `ToolConfig` for a tool, keyed by its id or one of its aliases.
+pub fn get_tool_config(app: &AppHandle, tool: &ToolDefinition) -> Option<ToolConfig> {
+    let mut configs = get_app_config(app.clone()).tool_configs?;
+    std::iter::once(&tool.id)
+        .chain(tool.aliases.iter())
+        .find_map(|key| configs.remove(key))
+}
 
-    let output = create_background_command(program)
-        .args(&args)
-        .output()
-        .map_err(|e| {
-            let err_msg = e.to_string();
-            println!("Install command failed to launch: {}", err_msg);
-            err_msg
-        })?;
+/// Environment a tool is launched with: PATH for the selected Node installation,
+/// plus the API key, base URL and model from its `ToolConfig` under the names
+/// the tool reads (`llm_env` in the manifest).
+pub fn tool_launch_env(app: &AppHandle, tool: &ToolDefinition) -> HashMap<String, String> {
+    let mut env = HashMap::new();
+    if let Some(path) = node_path(app) {
+        env.insert("PATH".to_string(), path.to_string_lossy().to_string());
+    }
 
-    if output.status.success() {
-        println!("npm install finished. Verifying {}...", tool_id);
-        // Verify installation by checking status
-        let status = check_tool_status(tool_id.clone());
-        if status.installed {
-            println!(
-                "Verification successful: {} is installed (v{:?}).",
-                tool_id, status.version
-            );
-            Ok("Installation successful".to_string())
-        } else {
-            println!("Verification failed: {} not found after install.", tool_id);
-            Err(format!(
-                "Installation appeared successful but tool {} was not found. Please check PATH.",
-                tool_id
-            ))
+    let Some(config) = get_tool_config(app, tool) else {
+        return env;
+    };
+    // Keys live in the secret store; plaintext is only left over from configs
+    // the startup migration couldn't move
+    let api_key = match resolve_secret(app, config.llm_api_key_id.as_deref()) {
+        Ok(Some(key)) => Some(key),
+        Ok(None) => config.llm_api_key,
+        Err(e) => {
+            println!("Failed to read API key for {}: {}", tool.id, e);
+            config.llm_api_key
+        }
+    };
+    let values = [
+        (&tool.llm_env.api_key, api_key),
+        (&tool.llm_env.base_url, config.llm_base_url),
+        (&tool.llm_env.model, config.llm_model),
+    ];
+    for (names, value) in values {
+        // An empty field means "use the tool's own default", not "set it to empty"
+        let Some(value) = value.filter(|v| !v.is_empty()) else {
+            continue;
+        };
+        for name in names {
+            env.insert(name.clone(), value.clone());
         }
-    } else {
-        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-        println!("Installation of {} failed. Stderr: {}", tool_id, stderr);
-        Err(stderr)
     }
+    env
+}
+
+/// Full path of a tool's program as the tool would be launched: the selected
+/// Node's PATH first, then the managed bin dir for archive installs.
+fn resolve_tool_program(app: &AppHandle, tool: &ToolDefinition) -> String {
+    let search_path = node_path(app).or_else(shell_path);
+    tool.status_programs()
+        .iter()
+        .find_map(|program| which::which_in_global(program, search_path.clone()).ok()?.next())
+        .or_else(|| managed_binary_path(app, &tool.program).ok().filter(|p| p.exists()))
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_else(|| tool.program.clone())
 }
 
+/// Runs a tool inside a new `AppPty` session instead of an external terminal
+/// and returns the session id for the UI to open as a tab. Args go to the
+/// program as-is, with no shell in between to re-split or expand them.
 #[tauri::command]
-pub async fn update_tool(tool_id: String) -> Result<String, String> {
-    let package = match tool_id.as_str() {
-        "claude" => "@anthropic-ai/claude-code@latest",
-        "google" => "@google/gemini-cli@latest",
-        "opencode" => "opencode-ai@latest",
-        "qoder" => "@qoder-ai/qodercli@latest",
-        "codebuddy" => "@tencent-ai/codebuddy-code@latest",
-        "copilot" => "@github/copilot@latest",
-        "codex" => "@openai/codex@latest",
-        _ => return Err("Update not supported for this tool".to_string()),
-    };
+pub fn launch_tool_in_pty(
+    app: AppHandle,
+    state: State<'_, AppPty>,
+    tool_id: String,
+    args: Option<Vec<String>>,
+    cols: Option<u16>,
+    rows: Option<u16>,
+) -> Result<String, String> {
+    let registry = ToolRegistry::load(&app);
+    let tool = registry.get(&tool_id).ok_or("Unknown tool")?;
 
-    let (program, args) = if cfg!(target_os = "windows") {
-        ("npm.cmd", vec!["install", "-g", package])
+    let program = resolve_tool_program(&app, tool);
+    let args = args.unwrap_or_default();
+    // npm installs .cmd shims on Windows, which only cmd.exe can run
+    let is_batch = program.to_lowercase().ends_with(".cmd") || program.to_lowercase().ends_with(".bat");
+    let (program, args) = if cfg!(target_os = "windows") && is_batch {
+        batch_shim_command(&program, args)?
     } else {
-        ("npm", vec!["install", "-g", package])
+        (program, args)
     };
 
-    let output = create_background_command(program)
-        .args(&args)
-        .output()
-        .map_err(|e| e.to_string())?;
+    // pty_open applies the tool's working directory and launch env
+    let session_id = new_job_id(&tool.id);
+    let options = PtyOpenOptions {
+        program: Some(program),
+        args: Some(args),
+        tool: Some(tool.id.clone()),
+        ..Default::default()
+    };
+    pty_open(
+        app.clone(),
+        state,
+        session_id.clone(),
+        cols.unwrap_or(80),
+        rows.unwrap_or(24),
+        Some(options),
+    )?;
+    Ok(session_id)
+}
 
-    if output.status.success() {
-        Ok("Update successful".to_string())
-    } else {
-        Err(String::from_utf8_lossy(&output.stderr).to_string())
+/// Turns a `.cmd`/`.bat` launch into a program and args the PTY can run.
+/// cmd.exe re-parses the command line (and the shim's `%*` re-expands it), so
+/// quoting can't make arbitrary args safe there. npm and pnpm shims just run
+/// node on a script, so those are run as `node <script>` directly; anything
+/// else goes through cmd.exe only if the args contain nothing cmd interprets.
+fn batch_shim_command(shim: &str, args: Vec<String>) -> Result<(String, Vec<String>), String> {
+    if let Some((node, script)) = resolve_node_shim(std::path::Path::new(shim)) {
+        let mut node_args = vec![script.to_string_lossy().to_string()];
+        node_args.extend(args);
+        return Ok((node, node_args));
     }
-}
 
-#[tauri::command]
-pub async fn uninstall_tool(tool_id: String) -> Result<String, String> {
-    let package = match tool_id.as_str() {
-        "claude" => "@anthropic-ai/claude-code",
-        "google" => "@google/gemini-cli",
-        "opencode" => "opencode-ai",
-        "qoder" => "@qoder-ai/qodercli",
-        "codebuddy" => "@tencent-ai/codebuddy-code",
-        "copilot" => "@github/copilot",
-        "codex" => "@openai/codex",
-        _ => return Err("Uninstall not supported for this tool".to_string()),
+    let cmd_safe = |arg: &str| {
+        arg.chars()
+            .all(|c| c.is_ascii_alphanumeric() || " -_./\\:=,@+".contains(c))
     };
+    if let Some(arg) = args.iter().find(|a| !cmd_safe(a)) {
+        return Err(format!(
+            "Cannot pass {:?} to {} safely: cmd.exe would interpret its special characters",
+            arg, shim
+        ));
+    }
+    let mut cmd_args = vec!["/C".to_string(), shim.to_string()];
+    cmd_args.extend(args);
+    Ok(("cmd.exe".to_string(), cmd_args))
+}
 
-    let (program, args) = if cfg!(target_os = "windows") {
-        ("npm.cmd", vec!["uninstall", "-g", package])
+/// Finds the script an npm/pnpm `.cmd` shim runs, e.g.
+/// `"%dp0%\node_modules\@anthropic-ai\claude-code\cli.js" %*`, and the node to
+/// run it with (a `node.exe` next to the shim wins, as in the shim itself).
+fn resolve_node_shim(shim: &std::path::Path) -> Option<(String, PathBuf)> {
+    let dir = shim.parent()?;
+    let content = fs::read_to_string(shim).ok()?;
+    let script = content
+        .split('"')
+        .filter_map(|token| {
+            token
+                .strip_prefix("%dp0%\\")
+                .or_else(|| token.strip_prefix("%~dp0\\"))
+        })
+        .rfind(|rel| !rel.to_lowercase().ends_with("node.exe"))?;
+    let script = dir.join(script);
+    if !script.is_file() {
+        return None;
+    }
+    let local_node = dir.join("node.exe");
+    let node = if local_node.is_file() {
+        local_node.to_string_lossy().to_string()
     } else {
-        ("npm", vec!["uninstall", "-g", package])
+        "node".to_string()
     };
+    Some((node, script))
+}
 
-    let output = create_background_command(program)
-        .args(&args)
-        .output()
-        .map_err(|e| e.to_string())?;
+/// A running install/update/uninstall job that can be cancelled by id. The entry
+/// exists for the whole job; `child` is only set while a package manager runs.
+/// Archive installs run in-process and poll `cancelled` instead.
+pub struct ToolJob {
+    child: Option<std::process::Child>,
+    cancelled: bool,
+}
 
-    if output.status.success() {
-        Ok("Uninstallation successful".to_string())
-    } else {
-        Err(String::from_utf8_lossy(&output.stderr).to_string())
+pub struct ToolJobs {
+    pub jobs: Arc<Mutex<HashMap<String, ToolJob>>>,
+}
+
+impl Default for ToolJobs {
+    fn default() -> Self {
+        Self {
+            jobs: Arc::new(Mutex::new(HashMap::new())),
+        }
     }
 }
 
-#[tauri::command]
-pub fn check_tool_status(tool_id: String) -> ToolStatus {
-    let (program, args) = match tool_id.as_str() {
-        "claude" => {
-            if cfg!(target_os = "windows") {
-                ("claude.cmd", vec!["--version"])
-            } else {
-                ("claude", vec!["--version"])
-            }
+/// How long a registry lookup for the latest version is reused.
+const UPDATE_CHECK_TTL: std::time::Duration = std::time::Duration::from_secs(60 * 60);
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ToolUpdateInfo {
+    pub tool_id: String,
+    pub current: Option<String>,
+    pub latest: Option<String>,
+    pub update_available: bool,
+    pub error: Option<String>,
+}
+
+/// Latest versions per tool, keyed by tool id, with the time they were fetched.
+#[derive(Default)]
+pub struct ToolUpdateCache {
+    pub entries: Mutex<HashMap<String, (std::time::Instant, Option<String>)>>,
+}
+
+static JOB_COUNTER: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+
+fn new_job_id(tool_id: &str) -> String {
+    let millis = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or_default();
+    // The counter keeps ids unique when two jobs start within the same millisecond
+    let seq = JOB_COUNTER.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    format!("{}-{}-{}", tool_id, millis, seq)
+}
+
+/// Signals a job's whole process tree. Package managers run lifecycle scripts
+/// as grandchildren that hold the output pipes open, so killing only the direct
+/// child would leave the job hanging.
+fn kill_job_tree(child: &mut std::process::Child) -> Result<(), String> {
+    #[cfg(unix)]
+    {
+        // Jobs are started as their own process group, whose id is the child's pid
+        let pgid = child.id() as libc::pid_t;
+        if unsafe { libc::kill(-pgid, libc::SIGTERM) } == 0 {
+            return Ok(());
         }
-        "google" => {
-            if cfg!(target_os = "windows") {
-                ("gemini.cmd", vec!["--version"])
-            } else {
-                ("gemini", vec!["--version"])
-            }
+    }
+
+    // npm.cmd runs node as a grandchild; kill the whole tree on Windows
+    #[cfg(windows)]
+    {
+        let _ = create_background_command("taskkill")
+            .args(["/PID", &child.id().to_string(), "/T", "/F"])
+            .output();
+    }
+
+    child.kill().map_err(|e| e.to_string())
+}
+
+fn emit_tool_log(app: &AppHandle, job_id: &str, tool_id: &str, stream: &str, line: &str) {
+    let _ = app.emit(
+        "tool-install-log",
+        serde_json::json!({
+            "jobId": job_id,
+            "toolId": tool_id,
+            "stream": stream,
+            "line": line
+        }),
+    );
+}
+
+/// Runs a tool job to completion, streaming each output line as a `tool-install-log`
+/// event. Blocks the calling thread, so run it via `spawn_blocking`.
+fn run_tool_job(
+    app: &AppHandle,
+    jobs: &Arc<Mutex<HashMap<String, ToolJob>>>,
+    job_id: &str,
+    tool_id: &str,
+    program: &str,
+    args: &[String],
+) -> Result<(), String> {
+    use std::io::BufRead;
+
+    println!("Executing tool job {}: {} {:?}", job_id, program, args);
+
+    let mut command = node_command(app, program);
+    command
+        .args(args)
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped());
+    // Own process group, so cancelling reaches lifecycle-script grandchildren too
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+    let mut child = command
+        .spawn()
+        .map_err(|e| format!("Failed to launch {}: {}", program, e))?;
+    let pid = child.id();
+
+    let stdout = child.stdout.take();
+    let stderr = child.stderr.take();
+    {
+        let mut jobs = jobs.lock().unwrap();
+        let job = jobs.entry(job_id.to_string()).or_insert(ToolJob {
+            child: None,
+            cancelled: false,
+        });
+        // Cancelled while we were spawning
+        if job.cancelled {
+            let _ = kill_job_tree(&mut child);
         }
-        "opencode" => {
-            if cfg!(target_os = "windows") {
-                ("opencode.cmd", vec!["--version"])
-            } else {
-                ("opencode", vec!["--version"])
+        job.child = Some(child);
+    }
+
+    // Keep the last stderr lines around to explain a failure
+    let stderr_tail = Arc::new(Mutex::new(Vec::<String>::new()));
+
+    let spawn_reader = |pipe: Box<dyn Read + Send>, stream: &'static str| {
+        let app = app.clone();
+        let job_id = job_id.to_string();
+        let tool_id = tool_id.to_string();
+        let stderr_tail = stderr_tail.clone();
+        thread::spawn(move || {
+            for line in std::io::BufReader::new(pipe).lines().map_while(Result::ok) {
+                if stream == "stderr" {
+                    let mut tail = stderr_tail.lock().unwrap();
+                    tail.push(line.clone());
+                    if tail.len() > 20 {
+                        tail.remove(0);
+                    }
+                }
+                emit_tool_log(&app, &job_id, &tool_id, stream, &line);
             }
-        }
-        "qoder" => {
-            if cfg!(target_os = "windows") {
-                ("qodercli.cmd", vec!["--version"])
-            } else {
-                ("qodercli", vec!["--version"])
+        })
+    };
+
+    let mut readers = Vec::new();
+    if let Some(out) = stdout {
+        readers.push(spawn_reader(Box::new(out), "stdout"));
+    }
+    if let Some(err) = stderr {
+        readers.push(spawn_reader(Box::new(err), "stderr"));
+    }
+
+    // Poll rather than block in wait() so cancel_tool_job can take the lock and kill
+    let (status, cancelled) = loop {
+        {
+            let mut jobs = jobs.lock().unwrap();
+            let Some(job) = jobs.get_mut(job_id) else {
+                return Err("Tool job disappeared".to_string());
+            };
+            let Some(child) = job.child.as_mut() else {
+                return Err("Tool job has no process".to_string());
+            };
+            match child.try_wait() {
+                Ok(Some(status)) => {
+                    job.child = None;
+                    break (status, job.cancelled);
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    job.child = None;
+                    return Err(e.to_string());
+                }
             }
         }
-        "codebuddy" => {
-            if cfg!(target_os = "windows") {
-                ("codebuddy.cmd", vec!["--version"])
-            } else {
-                ("codebuddy", vec!["--version"])
-            }
+        thread::sleep(std::time::Duration::from_millis(100));
+    };
+
+    // Anything in the group that ignored SIGTERM would keep the pipes open
+    #[cfg(unix)]
+    if cancelled {
+        unsafe {
+            libc::kill(-(pid as libc::pid_t), libc::SIGKILL);
         }
-        "copilot" => {
-            if cfg!(target_os = "windows") {
-                ("copilot.cmd", vec!["--version"])
-            } else {
-                ("copilot", vec!["--version"])
-            }
+    }
+    #[cfg(not(unix))]
+    let _ = pid;
+
+    for reader in readers {
+        let _ = reader.join();
+    }
+
+    if cancelled {
+        Err("Cancelled".to_string())
+    } else if status.success() {
+        Ok(())
+    } else {
+        let tail = stderr_tail.lock().unwrap().join("\n");
+        if tail.is_empty() {
+            Err(format!("{} exited with {}", program, status))
+        } else {
+            Err(tail)
         }
-        "codex" => {
-            if cfg!(target_os = "windows") {
-                ("codex.cmd", vec!["--version"])
-            } else {
-                ("codex", vec!["--version"])
+    }
+}
+
+/// Runs one action through the chosen install method: package managers as a child
+/// process, archives in-process with the same log events and cancellation.
+fn run_installer(
+    app: &AppHandle,
+    jobs: &Arc<Mutex<HashMap<String, ToolJob>>>,
+    job_id: &str,
+    tool: &ToolDefinition,
+    method: &InstallMethod,
+    action: ToolAction,
+    version: Option<&str>,
+) -> Result<(), String> {
+    if let Some((program, args)) = method.command(action, version) {
+        return run_tool_job(app, jobs, job_id, &tool.id, &program, &args);
+    }
+
+    let InstallMethod::Archive { urls, binary, version_url } = method else {
+        return Err(format!("{} does not support this action", method.name()));
+    };
+
+    if action == ToolAction::Uninstall {
+        return uninstall_archive(app, tool);
+    }
+
+    let log = |line: &str| emit_tool_log(app, job_id, &tool.id, "stdout", line);
+    let cancelled = || {
+        jobs.lock()
+            .unwrap()
+            .get(job_id)
+            .map(|j| j.cancelled)
+            .unwrap_or(false)
+    };
+    install_archive(app, tool, urls, binary, version_url.as_deref(), &log, &cancelled)
+}
+
+/// Updates the backend's record of a tool in `tool_statuses`: the method that installed
+/// it, so updates and removals use the same one, and the version it replaced.
+fn update_tool_record(
+    app: &AppHandle,
+    tool_id: &str,
+    update: impl FnOnce(&mut ToolStatus),
+) -> Result<(), String> {
+    let mut config = get_app_config(app.clone());
+    let statuses = config.tool_statuses.get_or_insert_with(HashMap::new);
+    let status = statuses.entry(tool_id.to_string()).or_insert_with(|| ToolStatus {
+        id: tool_id.to_string(),
+        installed: false,
+        version: None,
+        install_method: None,
+        previous_version: None,
+        node_install: None,
+    });
+    update(status);
+    write_app_config(app, &config)
+}
+
+/// Spawns a tool job off the async runtime and emits its final `tool-install-status`.
+async fn spawn_tool_job(
+    app: AppHandle,
+    jobs: Arc<Mutex<HashMap<String, ToolJob>>>,
+    job_id: Option<String>,
+    tool_id: String,
+    action: ToolAction,
+    method: Option<String>,
+    version: Option<String>,
+) -> Result<String, String> {
+    let job_id = job_id.unwrap_or_else(|| new_job_id(&tool_id));
+    {
+        // Reserve the id for the whole job so it can be cancelled from the start
+        // and a second job can't silently take it over
+        let mut jobs = jobs.lock().unwrap();
+        if jobs.contains_key(&job_id) {
+            return Err(format!("Tool job {} is already running", job_id));
+        }
+        jobs.insert(
+            job_id.clone(),
+            ToolJob {
+                child: None,
+                cancelled: false,
+            },
+        );
+    }
+
+    let task_app = app.clone();
+    let task_jobs = jobs.clone();
+    let task_job_id = job_id.clone();
+    let task_tool_id = tool_id.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        let registry = ToolRegistry::load(&task_app);
+        let tool = registry
+            .get(&task_tool_id)
+            .ok_or_else(|| format!("{} not supported for this tool", action.label()))?;
+
+        let config = get_app_config(task_app.clone());
+        let recorded = config
+            .tool_statuses
+            .as_ref()
+            .and_then(|s| s.get(&task_tool_id))
+            .and_then(|s| s.install_method.clone());
+        let installer = select_installer(tool, method.as_deref(), recorded.as_deref())?;
+        if version.is_some() && !installer.supports_versions() {
+            return Err(format!("{} cannot install a specific version", installer.name()));
+        }
+
+        if action == ToolAction::Uninstall {
+            run_installer(&task_app, &task_jobs, &task_job_id, tool, &installer, action, None)?;
+            return update_tool_record(&task_app, &task_tool_id, |s| s.install_method = None);
+        }
+
+        let before = check_tool_status(task_app.clone(), task_tool_id.clone())
+            .version
+            .and_then(|v| extract_version(&v));
+
+        run_installer(&task_app, &task_jobs, &task_job_id, tool, &installer, action, version.as_deref())?;
+
+        println!("{} finished. Verifying {}...", installer.name(), task_tool_id);
+        let status = check_tool_status(task_app.clone(), task_tool_id.clone());
+        if action == ToolAction::Install && !status.installed {
+            return Err(format!(
+                "Installation appeared successful but tool {} was not found. Please check PATH.",
+                task_tool_id
+            ));
+        }
+        let after = status.version.and_then(|v| extract_version(&v));
+
+        update_tool_record(&task_app, &task_tool_id, |s| {
+            s.install_method = Some(installer.name().to_string());
+            // Reinstalling the same version keeps the older rollback target
+            if before.is_some() && before != after {
+                s.previous_version = before;
+            }
+        })?;
+        Ok(())
+    })
+    .await
+    .map_err(|e| e.to_string())
+    .and_then(|r| r);
+    jobs.lock().unwrap().remove(&job_id);
+
+    let (status, message) = match &result {
+        Ok(()) => ("success", format!("{} successful", action.label())),
+        Err(e) if e == "Cancelled" => ("cancelled", e.clone()),
+        Err(e) => ("failed", e.clone()),
+    };
+
+    let _ = app.emit(
+        "tool-install-status",
+        serde_json::json!({
+            "jobId": job_id,
+            "toolId": tool_id,
+            "action": action.label(),
+            "status": status,
+            "message": message
+        }),
+    );
+
+    result.map(|_| message)
+}
+
+#[tauri::command]
+pub async fn install_tool(
+    app: AppHandle,
+    jobs: State<'_, ToolJobs>,
+    tool_id: String,
+    job_id: Option<String>,
+    method: Option<String>,
+    version: Option<String>,
+) -> Result<String, String> {
+    spawn_tool_job(app, jobs.jobs.clone(), job_id, tool_id, ToolAction::Install, method, version).await
+}
+
+#[tauri::command]
+pub async fn update_tool(
+    app: AppHandle,
+    jobs: State<'_, ToolJobs>,
+    tool_id: String,
+    job_id: Option<String>,
+    method: Option<String>,
+    version: Option<String>,
+) -> Result<String, String> {
+    spawn_tool_job(app, jobs.jobs.clone(), job_id, tool_id, ToolAction::Update, method, version).await
+}
+
+#[tauri::command]
+pub async fn uninstall_tool(
+    app: AppHandle,
+    jobs: State<'_, ToolJobs>,
+    tool_id: String,
+    job_id: Option<String>,
+    method: Option<String>,
+) -> Result<String, String> {
+    spawn_tool_job(app, jobs.jobs.clone(), job_id, tool_id, ToolAction::Uninstall, method, None).await
+}
+
+/// Reinstalls the version recorded before the last install or update.
+#[tauri::command]
+pub async fn rollback_tool(
+    app: AppHandle,
+    jobs: State<'_, ToolJobs>,
+    tool_id: String,
+    job_id: Option<String>,
+) -> Result<String, String> {
+    let previous = get_app_config(app.clone())
+        .tool_statuses
+        .and_then(|s| s.get(&tool_id).and_then(|t| t.previous_version.clone()))
+        .ok_or_else(|| format!("No previous version of {} recorded", tool_id))?;
+
+    spawn_tool_job(app, jobs.jobs.clone(), job_id, tool_id, ToolAction::Update, None, Some(previous)).await
+}
+
+#[tauri::command]
+pub async fn list_tool_versions(app: AppHandle, tool_id: String) -> Result<ToolVersions, String> {
+    let registry = ToolRegistry::load(&app);
+    let tool = registry
+        .get(&tool_id)
+        .ok_or_else(|| format!("Unknown tool: {}", tool_id))?;
+    let package = tool_npm_package(tool)
+        .ok_or_else(|| format!("{} is not published to npm", tool_id))?;
+
+    let (dist_tags, versions) = fetch_npm_versions(&app, &package).await?;
+    Ok(ToolVersions {
+        tool_id,
+        package,
+        dist_tags,
+        versions,
+    })
+}
+
+#[tauri::command]
+pub fn cancel_tool_job(jobs: State<'_, ToolJobs>, job_id: String) -> Result<(), String> {
+    let mut jobs = jobs.jobs.lock().unwrap();
+    let job = jobs
+        .get_mut(&job_id)
+        .ok_or_else(|| "Tool job not found".to_string())?;
+
+    job.cancelled = true;
+
+    let Some(child) = job.child.as_mut() else {
+        // Archive downloads notice the flag between chunks, and a job that
+        // hasn't spawned yet kills its child as soon as it does
+        return Ok(());
+    };
+
+    kill_job_tree(child)
+}
+
+/// Latest version of a tool from the registry of the method that installed it
+/// (or its preferred one), served from the cache while fresh.
+async fn latest_tool_version(
+    app: &AppHandle,
+    cache: &ToolUpdateCache,
+    tool: &ToolDefinition,
+    recorded: Option<&str>,
+    force: bool,
+) -> Result<Option<String>, String> {
+    if !force {
+        if let Some((fetched, latest)) = cache.entries.lock().unwrap().get(&tool.id) {
+            if fetched.elapsed() < UPDATE_CHECK_TTL {
+                return Ok(latest.clone());
             }
         }
-        _ => {
-            return ToolStatus {
-                id: tool_id,
-                installed: false,
-                version: None,
+    }
+
+    let installers = tool.installers();
+    let Some(method) = recorded
+        .and_then(|name| installers.iter().find(|m| m.name() == name))
+        .or_else(|| installers.first())
+    else {
+        return Ok(None);
+    };
+
+    let latest = fetch_latest_version(app, method).await?;
+    cache
+        .entries
+        .lock()
+        .unwrap()
+        .insert(tool.id.clone(), (std::time::Instant::now(), latest.clone()));
+    Ok(latest)
+}
+
+/// Compares installed tools against their registries. Defaults to every registered
+/// tool; `force` skips the cache.
+#[tauri::command]
+pub async fn check_tool_updates(
+    app: AppHandle,
+    cache: State<'_, ToolUpdateCache>,
+    tool_ids: Option<Vec<String>>,
+    force: Option<bool>,
+) -> Result<Vec<ToolUpdateInfo>, String> {
+    let registry = ToolRegistry::load(&app);
+    let tool_ids = tool_ids.unwrap_or_else(|| registry.tools.iter().map(|t| t.id.clone()).collect());
+
+    let statuses = {
+        let app = app.clone();
+        let tool_ids = tool_ids.clone();
+        tauri::async_runtime::spawn_blocking(move || {
+            tool_ids
+                .into_iter()
+                .map(|id| check_tool_status(app.clone(), id))
+                .collect::<Vec<_>>()
+        })
+        .await
+        .map_err(|e| e.to_string())?
+    };
+
+    let checks = statuses.into_iter().map(|status| {
+        let app = &app;
+        let cache = &*cache;
+        let registry = &registry;
+        async move {
+            let current = status.version.as_deref().and_then(extract_version);
+            let mut info = ToolUpdateInfo {
+                tool_id: status.id.clone(),
+                current: current.clone(),
+                latest: None,
+                update_available: false,
+                error: None,
+            };
+            // Nothing to compare against for tools that aren't installed
+            let (Some(tool), Some(current)) = (registry.get(&status.id), current) else {
+                return info;
+            };
+
+            let force = force.unwrap_or(false);
+            match latest_tool_version(app, cache, tool, status.install_method.as_deref(), force).await {
+                Ok(latest) => {
+                    info.update_available = match (
+                        semver::Version::parse(&current),
+                        latest.as_deref().map(semver::Version::parse),
+                    ) {
+                        (Ok(current), Some(Ok(latest))) => latest > current,
+                        _ => false,
+                    };
+                    info.latest = latest;
+                }
+                Err(e) => info.error = Some(e),
             }
+            info
         }
+    });
+
+    Ok(futures_util::future::join_all(checks).await)
+}
+
+#[tauri::command]
+pub fn check_tool_status(app: AppHandle, tool_id: String) -> ToolStatus {
+    let recorded = get_app_config(app.clone())
+        .tool_statuses
+        .and_then(|mut s| s.remove(&tool_id));
+    let install_method = recorded.as_ref().and_then(|t| t.install_method.clone());
+    let previous_version = recorded.and_then(|t| t.previous_version);
+
+    let registry = ToolRegistry::load(&app);
+    let Some(tool) = registry.get(&tool_id) else {
+        return ToolStatus {
+            id: tool_id,
+            installed: false,
+            version: None,
+            install_method,
+            previous_version,
+            node_install: None,
+        };
     };
 
-    let version = get_version(program, &args);
+    let args: Vec<&str> = tool.version_args.iter().map(|s| s.as_str()).collect();
+    // Resolve against the selected Node's PATH so tools installed under it are found
+    let search_path = node_path(&app).or_else(shell_path);
+    let found = tool.status_programs().iter().find_map(|program| {
+        let path = which::which_in_global(program, search_path.clone()).ok()?.next()?;
+        let mut cmd = node_command(&app, &path.to_string_lossy());
+        cmd.args(&args);
+        get_command_version(cmd).map(|version| (path, version))
+    });
+    let node_install = found
+        .as_ref()
+        .and_then(|(path, _)| node_install_for(path, &detect_node_installs(&app)));
+    let mut version = found.map(|(_, version)| version);
+
+    // Archive installs live in the managed bin dir, which usually isn't on PATH
+    if version.is_none() {
+        if let Ok(path) = managed_binary_path(&app, &tool.program) {
+            if path.exists() {
+                version = get_version(&path.to_string_lossy(), &args);
+            }
+        }
+    }
 
     ToolStatus {
         id: tool_id,
         installed: version.is_some(),
         version,
+        install_method,
+        previous_version,
+        node_install,
     }
 }
-
@@ -15,6 +15,8 @@ use std::sync::{Arc, Mutex};
 use std::thread;
 use tauri::{AppHandle, Emitter, Manager, State};
 
+use tauri_plugin_fs::FsExt;
+
 #[cfg(windows)]
 use std::os::windows::process::CommandExt;
 
@@ -325,3 +327,63 @@ pub fn open_folder(path: String) -> Result<(), String> {
     opener::open(&path).map_err(|e| e.to_string())
 }
 
+/// Canonical form of a directory the renderer asks to open up. Symlinks and `..`
+/// are resolved so the scope and the dedupe see the real directory; the
+/// filesystem root and the home directory are refused outright.
+fn checked_scope_directory(path: &str) -> Result<PathBuf, String> {
+    let dir = dunce::canonicalize(path).map_err(|e| format!("Cannot access {}: {}", path, e))?;
+    if !dir.is_dir() {
+        return Err(format!("Not a directory: {}", path));
+    }
+    if dir.parent().is_none() {
+        return Err(format!("Refusing to allow the filesystem root: {}", path));
+    }
+    let home = std::env::var("HOME")
+        .or_else(|_| std::env::var("USERPROFILE"))
+        .ok()
+        .and_then(|home| dunce::canonicalize(home).ok());
+    if home.as_deref() == Some(dir.as_path()) {
+        return Err("Refusing to allow the whole home directory; pick a project folder".to_string());
+    }
+    Ok(dir)
+}
+
+/// Re-applies the persisted project directories to the fs plugin scope.
+/// Called once from `setup` since runtime scope changes don't survive a restart.
+pub fn apply_allowed_directories(app: &AppHandle) {
+    let config = get_app_config(app.clone());
+    for dir in config.allowed_directories.unwrap_or_default() {
+        let dir = match checked_scope_directory(&dir) {
+            Ok(dir) => dir,
+            Err(e) => {
+                println!("Skipping allowed directory: {}", e);
+                continue;
+            }
+        };
+        if let Err(e) = app.fs_scope().allow_directory(&dir, true) {
+            println!("Failed to allow directory {}: {}", dir.display(), e);
+        }
+    }
+}
+
+#[tauri::command]
+pub fn allow_directory(app: AppHandle, path: String) -> Result<(), String> {
+    let dir = checked_scope_directory(&path)?;
+
+    app.fs_scope()
+        .allow_directory(&dir, true)
+        .map_err(|e| e.to_string())?;
+
+    // Persist so the scope can be restored on the next launch
+    let mut config = get_app_config(app.clone());
+    let dirs = config.allowed_directories.get_or_insert_with(Vec::new);
+    let known = dirs
+        .iter()
+        .any(|d| dunce::canonicalize(d).map(|d| d == dir).unwrap_or(false));
+    if !known {
+        dirs.push(dir.to_string_lossy().to_string());
+        write_app_config(&app, &config)?;
+    }
+
+    Ok(())
+}
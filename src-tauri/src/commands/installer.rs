@@ -0,0 +1,491 @@
+#![allow(unused_imports)]
+#![allow(dead_code)]
+
+use futures_util::StreamExt;
+use portable_pty::{Child, CommandBuilder, MasterPty, NativePtySystem, PtySize, PtySystem};
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::cmp::min;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+#[cfg(windows)]
+use std::os::windows::process::CommandExt;
+
+use crate::commands::pty::*;
+use crate::commands::env::*;
+use crate::commands::config::*;
+use crate::commands::fs::*;
+use crate::commands::chat::*;
+use crate::commands::registry::*;
+use crate::commands::shell_env::*;
+use crate::commands::skills::*;
+use crate::commands::utils::*;
+
+/// How a tool gets onto the machine. Each tool lists one or more of these in
+/// `tools.json`; the first whose package manager is present wins unless the
+/// user asks for a specific one.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "method", rename_all = "lowercase")]
+pub enum InstallMethod {
+    Npm { package: String },
+    Pnpm { package: String },
+    Bun { package: String },
+    Pipx { package: String },
+    Uv { package: String },
+    Brew { formula: String },
+    /// Download a release asset for the current platform (keyed like "linux-x64")
+    /// and put `binary` into the managed bin dir. Hosts that publish versioned
+    /// paths set `version_url` to a text file with the latest version, which
+    /// replaces `{version}` in the urls.
+    Archive {
+        urls: HashMap<String, String>,
+        binary: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        version_url: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToolAction {
+    Install,
+    Update,
+    Uninstall,
+}
+
+impl ToolAction {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ToolAction::Install => "Installation",
+            ToolAction::Update => "Update",
+            ToolAction::Uninstall => "Uninstallation",
+        }
+    }
+}
+
+impl InstallMethod {
+    pub fn name(&self) -> &'static str {
+        match self {
+            InstallMethod::Npm { .. } => "npm",
+            InstallMethod::Pnpm { .. } => "pnpm",
+            InstallMethod::Bun { .. } => "bun",
+            InstallMethod::Pipx { .. } => "pipx",
+            InstallMethod::Uv { .. } => "uv",
+            InstallMethod::Brew { .. } => "brew",
+            InstallMethod::Archive { .. } => "archive",
+        }
+    }
+
+    /// Package manager executable this method shells out to; None for archives.
+    pub fn manager(&self) -> Option<&'static str> {
+        let windows = cfg!(target_os = "windows");
+        match self {
+            InstallMethod::Npm { .. } => Some(if windows { "npm.cmd" } else { "npm" }),
+            InstallMethod::Pnpm { .. } => Some(if windows { "pnpm.cmd" } else { "pnpm" }),
+            InstallMethod::Bun { .. } => Some("bun"),
+            InstallMethod::Pipx { .. } => Some("pipx"),
+            InstallMethod::Uv { .. } => Some("uv"),
+            InstallMethod::Brew { .. } => Some("brew"),
+            InstallMethod::Archive { .. } => None,
+        }
+    }
+
+    pub fn is_available(&self) -> bool {
+        match self {
+            InstallMethod::Archive { urls, .. } => urls.contains_key(&platform_key()),
+            _ => self
+                .manager()
+                .map(|m| find_program(m).is_some())
+                .unwrap_or(false),
+        }
+    }
+
+    /// Whether a specific version or dist-tag can be requested through this method.
+    pub fn supports_versions(&self) -> bool {
+        !matches!(self, InstallMethod::Brew { .. } | InstallMethod::Archive { .. })
+    }
+
+    /// The npm package behind this method, used to look versions up in the registry.
+    pub fn npm_package(&self) -> Option<&str> {
+        match self {
+            InstallMethod::Npm { package }
+            | InstallMethod::Pnpm { package }
+            | InstallMethod::Bun { package } => Some(package),
+            _ => None,
+        }
+    }
+
+    /// Package manager invocation for an action; None for archives, which are handled in-process.
+    /// `version` is a version or dist-tag; updates default to the latest release.
+    pub fn command(&self, action: ToolAction, version: Option<&str>) -> Option<(String, Vec<String>)> {
+        let manager = self.manager()?.to_string();
+        let version = version.filter(|v| !v.is_empty());
+        let npm_spec = |package: &str| match (action, version) {
+            (_, Some(v)) => format!("{}@{}", package, v),
+            (ToolAction::Update, None) => format!("{}@latest", package),
+            _ => package.to_string(),
+        };
+        let python_spec = |package: &str| match version {
+            Some(v) => format!("{}=={}", package, v),
+            None => package.to_string(),
+        };
+
+        let args: Vec<String> = match (self, action) {
+            (InstallMethod::Npm { package }, ToolAction::Uninstall) => vec!["uninstall".into(), "-g".into(), package.clone()],
+            (InstallMethod::Npm { package }, _) => vec!["install".into(), "-g".into(), npm_spec(package)],
+            (InstallMethod::Pnpm { package } | InstallMethod::Bun { package }, ToolAction::Uninstall) => {
+                vec!["remove".into(), "-g".into(), package.clone()]
+            }
+            (InstallMethod::Pnpm { package } | InstallMethod::Bun { package }, _) => {
+                vec!["add".into(), "-g".into(), npm_spec(package)]
+            }
+            // Pinning through pipx/uv means reinstalling the exact version over the current one
+            (InstallMethod::Pipx { package }, ToolAction::Uninstall) => vec!["uninstall".into(), package.clone()],
+            (InstallMethod::Pipx { package }, _) if version.is_some() => {
+                vec!["install".into(), "--force".into(), python_spec(package)]
+            }
+            (InstallMethod::Pipx { package }, ToolAction::Install) => vec!["install".into(), package.clone()],
+            (InstallMethod::Pipx { package }, ToolAction::Update) => vec!["upgrade".into(), package.clone()],
+            (InstallMethod::Uv { package }, ToolAction::Uninstall) => {
+                vec!["tool".into(), "uninstall".into(), package.clone()]
+            }
+            (InstallMethod::Uv { package }, _) if version.is_some() => {
+                vec!["tool".into(), "install".into(), "--force".into(), python_spec(package)]
+            }
+            (InstallMethod::Uv { package }, ToolAction::Install) => vec!["tool".into(), "install".into(), package.clone()],
+            (InstallMethod::Uv { package }, ToolAction::Update) => vec!["tool".into(), "upgrade".into(), package.clone()],
+            (InstallMethod::Brew { formula }, ToolAction::Install) => vec!["install".into(), formula.clone()],
+            (InstallMethod::Brew { formula }, ToolAction::Update) => vec!["upgrade".into(), formula.clone()],
+            (InstallMethod::Brew { formula }, ToolAction::Uninstall) => vec!["uninstall".into(), formula.clone()],
+            (InstallMethod::Archive { .. }, _) => return None,
+        };
+        Some((manager, args))
+    }
+}
+
+/// Platform key used for archive URLs, e.g. "linux-x64", "darwin-arm64", "windows-x64".
+pub fn platform_key() -> String {
+    let os = match std::env::consts::OS {
+        "macos" => "darwin",
+        other => other,
+    };
+    let arch = match std::env::consts::ARCH {
+        "x86_64" => "x64",
+        "aarch64" => "arm64",
+        other => other,
+    };
+    format!("{}-{}", os, arch)
+}
+
+/// Picks the installer for a tool: the one explicitly requested, else the one that
+/// installed it before (so updates and removals go through the same method), else
+/// the first one usable on this machine.
+pub fn select_installer(
+    tool: &ToolDefinition,
+    requested: Option<&str>,
+    recorded: Option<&str>,
+) -> Result<InstallMethod, String> {
+    let installers = tool.installers();
+    if installers.is_empty() {
+        return Err(format!("No install method defined for {}", tool.id));
+    }
+
+    if let Some(name) = requested {
+        return installers
+            .into_iter()
+            .find(|m| m.name() == name)
+            .ok_or_else(|| format!("{} cannot be installed with {}", tool.id, name));
+    }
+
+    if let Some(name) = recorded {
+        if let Some(method) = installers.iter().find(|m| m.name() == name) {
+            return Ok(method.clone());
+        }
+    }
+
+    installers
+        .iter()
+        .find(|m| m.is_available())
+        .cloned()
+        .ok_or_else(|| {
+            let names: Vec<&str> = installers.iter().map(|m| m.name()).collect();
+            format!("None of the install methods for {} are available: {}", tool.id, names.join(", "))
+        })
+}
+
+pub fn get_managed_bin_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(app.path().app_data_dir().map_err(|e| e.to_string())?.join("bin"))
+}
+
+/// Path of a tool's binary inside the managed bin dir, whether or not it exists.
+pub fn managed_binary_path(app: &AppHandle, program: &str) -> Result<PathBuf, String> {
+    let name = if cfg!(target_os = "windows") && !program.ends_with(".exe") {
+        format!("{}.exe", program)
+    } else {
+        program.to_string()
+    };
+    Ok(get_managed_bin_dir(app)?.join(name))
+}
+
+fn find_file(dir: &std::path::Path, name: &str) -> Option<PathBuf> {
+    for entry in fs::read_dir(dir).ok()?.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            if let Some(found) = find_file(&path, name) {
+                return Some(found);
+            }
+        } else if path.file_name().and_then(|n| n.to_str()) == Some(name) {
+            return Some(path);
+        }
+    }
+    None
+}
+
+/// Downloads the platform's release asset, extracts it if needed, and copies the
+/// tool binary into the managed bin dir. `cancelled` is polled between chunks.
+pub fn install_archive(
+    app: &AppHandle,
+    tool: &ToolDefinition,
+    urls: &HashMap<String, String>,
+    binary: &str,
+    version_url: Option<&str>,
+    log: &dyn Fn(&str),
+    cancelled: &dyn Fn() -> bool,
+) -> Result<(), String> {
+    let platform = platform_key();
+    let url = urls
+        .get(&platform)
+        .ok_or_else(|| format!("No release asset for {}", platform))?;
+    let url = &match version_url.filter(|_| url.contains("{version}")) {
+        Some(version_url) => {
+            let version = tauri::async_runtime::block_on(fetch_text(app, version_url))?;
+            log(&format!("Latest version is {}", version));
+            url.replace("{version}", &version)
+        }
+        None => url.clone(),
+    };
+
+    let staging = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?
+        .join("tools")
+        .join(&tool.id);
+    if staging.exists() {
+        fs::remove_dir_all(&staging).map_err(|e| e.to_string())?;
+    }
+    fs::create_dir_all(&staging).map_err(|e| e.to_string())?;
+
+    let file_name = url.rsplit('/').next().unwrap_or("download");
+    let download_path = staging.join(file_name);
+    log(&format!("Downloading {}", url));
+
+    let client = get_proxy_client(app)?;
+    tauri::async_runtime::block_on(async {
+        let res = client
+            .get(url)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to connect: {}", e))?;
+        if !res.status().is_success() {
+            return Err(format!("Download failed: {}", res.status()));
+        }
+
+        let total = res.content_length().unwrap_or(0);
+        let mut file = fs::File::create(&download_path)
+            .map_err(|e| format!("Failed to create file: {}", e))?;
+        let mut downloaded: u64 = 0;
+        let mut last_percent = 0;
+        let mut stream = res.bytes_stream();
+        while let Some(item) = stream.next().await {
+            if cancelled() {
+                return Err("Cancelled".to_string());
+            }
+            let chunk = item.map_err(|e| format!("Error while downloading chunk: {}", e))?;
+            file.write_all(&chunk)
+                .map_err(|e| format!("Error while writing to file: {}", e))?;
+            downloaded += chunk.len() as u64;
+            // Content length is unknown for some release hosts; log progress only when we have it
+            if let Some(percent) = (downloaded * 100).checked_div(total) {
+                if percent >= last_percent + 10 {
+                    last_percent = percent;
+                    log(&format!("Downloaded {}%", percent));
+                }
+            }
+        }
+        Ok(())
+    })?;
+
+    let extracted = if file_name.ends_with(".zip") {
+        log("Extracting archive");
+        let file = fs::File::open(&download_path).map_err(|e| e.to_string())?;
+        zip_extract::extract(file, &staging, true)
+            .map_err(|e| format!("ZIP extraction failed: {}", e))?;
+        true
+    } else if file_name.ends_with(".tar.gz") || file_name.ends_with(".tgz") {
+        log("Extracting archive");
+        let file = fs::File::open(&download_path).map_err(|e| e.to_string())?;
+        tar::Archive::new(flate2::read::GzDecoder::new(file))
+            .unpack(&staging)
+            .map_err(|e| format!("TAR extraction failed: {}", e))?;
+        true
+    } else {
+        false
+    };
+
+    // A bare binary download is used as-is
+    let source = if extracted {
+        find_file(&staging, binary)
+            .or_else(|| find_file(&staging, &format!("{}.exe", binary)))
+            .ok_or_else(|| format!("{} not found in the downloaded archive", binary))?
+    } else {
+        download_path
+    };
+
+    let target = managed_binary_path(app, &tool.program)?;
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    fs::copy(&source, &target).map_err(|e| format!("Failed to install binary: {}", e))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&target, fs::Permissions::from_mode(0o755)).map_err(|e| e.to_string())?;
+    }
+
+    let _ = fs::remove_dir_all(&staging);
+    log(&format!("Installed {}", target.to_string_lossy()));
+    Ok(())
+}
+
+pub fn uninstall_archive(app: &AppHandle, tool: &ToolDefinition) -> Result<(), String> {
+    let target = managed_binary_path(app, &tool.program)?;
+    if target.exists() {
+        fs::remove_file(&target).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ToolVersions {
+    pub tool_id: String,
+    pub package: String,
+    /// Dist-tags such as "latest" or "next", mapped to the version they point at
+    pub dist_tags: HashMap<String, String>,
+    /// Published versions, newest first
+    pub versions: Vec<String>,
+}
+
+/// npm package whose registry entry describes a tool's releases.
+pub fn tool_npm_package(tool: &ToolDefinition) -> Option<String> {
+    tool.npm_package.clone().or_else(|| {
+        tool.installers
+            .iter()
+            .find_map(|m| m.npm_package().map(|p| p.to_string()))
+    })
+}
+
+/// Fetches dist-tags and published versions of a package from the npm registry.
+pub async fn fetch_npm_versions(
+    app: &AppHandle,
+    package: &str,
+) -> Result<(HashMap<String, String>, Vec<String>), String> {
+    // Scoped names keep the '@' but need the slash escaped
+    let url = format!("https://registry.npmjs.org/{}", package.replace('/', "%2F"));
+    let client = get_proxy_client(app)?;
+    let res = client
+        .get(&url)
+        // The abbreviated document is a fraction of the size and has everything we need
+        .header("Accept", "application/vnd.npm.install-v1+json")
+        .send()
+        .await
+        .map_err(|e| format!("Failed to connect: {}", e))?;
+    if !res.status().is_success() {
+        return Err(format!("Registry request failed: {}", res.status()));
+    }
+
+    let body: serde_json::Value = res.json().await.map_err(|e| e.to_string())?;
+    let dist_tags: HashMap<String, String> = body["dist-tags"]
+        .as_object()
+        .map(|tags| {
+            tags.iter()
+                .filter_map(|(tag, v)| v.as_str().map(|v| (tag.clone(), v.to_string())))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let mut versions: Vec<semver::Version> = body["versions"]
+        .as_object()
+        .map(|v| v.keys().filter_map(|k| semver::Version::parse(k).ok()).collect())
+        .unwrap_or_default();
+    versions.sort();
+    versions.reverse();
+
+    Ok((dist_tags, versions.iter().map(|v| v.to_string()).collect()))
+}
+
+/// Latest published version of the package behind an install method. Archives
+/// without a `version_url` have no registry to ask and yield None.
+pub async fn fetch_latest_version(app: &AppHandle, method: &InstallMethod) -> Result<Option<String>, String> {
+    match method {
+        InstallMethod::Npm { package } | InstallMethod::Pnpm { package } | InstallMethod::Bun { package } => {
+            let (dist_tags, _) = fetch_npm_versions(app, package).await?;
+            Ok(dist_tags.get("latest").cloned())
+        }
+        InstallMethod::Pipx { package } | InstallMethod::Uv { package } => {
+            let url = format!("https://pypi.org/pypi/{}/json", package);
+            let body = fetch_json(app, &url).await?;
+            Ok(body["info"]["version"].as_str().map(|v| v.to_string()))
+        }
+        InstallMethod::Brew { formula } => {
+            // Only core formulae are in the JSON API; tapped ones have no registry to ask
+            if formula.contains('/') {
+                return Ok(None);
+            }
+            let url = format!("https://formulae.brew.sh/api/formula/{}.json", formula);
+            let body = fetch_json(app, &url).await?;
+            Ok(body["versions"]["stable"].as_str().map(|v| v.to_string()))
+        }
+        InstallMethod::Archive { version_url: Some(url), .. } => fetch_text(app, url).await.map(Some),
+        InstallMethod::Archive { .. } => Ok(None),
+    }
+}
+
+/// A one-line text resource, e.g. a release host's "latest" version file.
+async fn fetch_text(app: &AppHandle, url: &str) -> Result<String, String> {
+    let client = get_proxy_client(app)?;
+    let res = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to connect: {}", e))?;
+    if !res.status().is_success() {
+        return Err(format!("Version request failed: {}", res.status()));
+    }
+    let text = res.text().await.map_err(|e| e.to_string())?;
+    let text = text.trim();
+    // The value is spliced into a download URL
+    if text.is_empty() || !text.chars().all(|c| c.is_ascii_alphanumeric() || ".-+_".contains(c)) {
+        return Err(format!("Unexpected version {:?} from {}", text, url));
+    }
+    Ok(text.to_string())
+}
+
+async fn fetch_json(app: &AppHandle, url: &str) -> Result<serde_json::Value, String> {
+    let client = get_proxy_client(app)?;
+    let res = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to connect: {}", e))?;
+    if !res.status().is_success() {
+        return Err(format!("Registry request failed: {}", res.status()));
+    }
+    res.json().await.map_err(|e| e.to_string())
+}
@@ -1,7 +1,17 @@
 pub mod chat;
 pub mod config;
+pub mod config_file;
+pub mod doctor;
 pub mod env;
 pub mod fs;
+pub mod installer;
+pub mod node;
+pub mod ollama;
+pub mod probes;
 pub mod pty;
+pub mod recording;
+pub mod registry;
+pub mod secrets;
+pub mod shell_env;
 pub mod skills;
 pub mod utils;
@@ -0,0 +1,294 @@
+#![allow(unused_imports)]
+#![allow(dead_code)]
+
+use futures_util::StreamExt;
+use portable_pty::{Child, CommandBuilder, MasterPty, NativePtySystem, PtySize, PtySystem};
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::cmp::min;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+#[cfg(windows)]
+use std::os::windows::process::CommandExt;
+
+use crate::commands::pty::*;
+use crate::commands::registry::*;
+use crate::commands::shell_env::*;
+use crate::commands::env::*;
+use crate::commands::config::*;
+use crate::commands::fs::*;
+use crate::commands::chat::*;
+use crate::commands::skills::*;
+use crate::commands::utils::*;
+
+/// A Node.js installation found on this machine.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NodeInstall {
+    /// "<manager>@<version>", e.g. "nvm@20.11.0"; stored in `AppConfig.node_install`
+    pub id: String,
+    /// "nvm", "fnm", "volta" or "system"
+    pub manager: String,
+    pub version: String,
+    /// Directory holding `node` and the global package shims
+    pub bin_dir: String,
+    /// Everything installed with this Node lives under here
+    pub root: String,
+    pub selected: bool,
+}
+
+/// Looks a variable up in the login-shell environment first; NVM_DIR and
+/// friends are usually only set in rc files.
+fn env_var(name: &str) -> Option<String> {
+    shell_env()
+        .and_then(|env| env.get(name).cloned())
+        .or_else(|| std::env::var(name).ok())
+        .filter(|v| !v.is_empty())
+}
+
+/// Version directories (named like "v20.11.0" or "20.11.0") under `dir`.
+fn version_dirs(dir: &std::path::Path) -> Vec<(String, PathBuf)> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter(|e| e.path().is_dir())
+        .filter_map(|e| {
+            let name = e.file_name().to_string_lossy().to_string();
+            let version = name.trim_start_matches('v').to_string();
+            semver::Version::parse(&version).ok()?;
+            Some((version, e.path()))
+        })
+        .collect()
+}
+
+fn node_install(manager: &str, version: String, root: PathBuf, bin_dir: PathBuf) -> NodeInstall {
+    NodeInstall {
+        id: format!("{}@{}", manager, version),
+        manager: manager.to_string(),
+        version,
+        bin_dir: bin_dir.to_string_lossy().to_string(),
+        root: root.to_string_lossy().to_string(),
+        selected: false,
+    }
+}
+
+fn detect_nvm() -> Vec<NodeInstall> {
+    if cfg!(target_os = "windows") {
+        // nvm-windows keeps each version's node.exe directly in its version directory
+        let Some(home) = env_var("NVM_HOME").or_else(|| env_var("APPDATA").map(|a| format!("{}\\nvm", a))) else {
+            return Vec::new();
+        };
+        return version_dirs(&PathBuf::from(home))
+            .into_iter()
+            .map(|(version, dir)| node_install("nvm", version, dir.clone(), dir))
+            .collect();
+    }
+
+    let Some(nvm_dir) = env_var("NVM_DIR")
+        .map(PathBuf::from)
+        .or_else(|| expand_home("~/.nvm").ok())
+    else {
+        return Vec::new();
+    };
+    version_dirs(&nvm_dir.join("versions").join("node"))
+        .into_iter()
+        .map(|(version, dir)| node_install("nvm", version, dir.clone(), dir.join("bin")))
+        .collect()
+}
+
+fn detect_fnm() -> Vec<NodeInstall> {
+    let mut candidates: Vec<PathBuf> = Vec::new();
+    if let Some(dir) = env_var("FNM_DIR") {
+        candidates.push(PathBuf::from(dir));
+    }
+    if cfg!(target_os = "windows") {
+        if let Some(appdata) = env_var("APPDATA") {
+            candidates.push(PathBuf::from(appdata).join("fnm"));
+        }
+    } else if cfg!(target_os = "macos") {
+        candidates.extend(expand_home("~/Library/Application Support/fnm").ok());
+    } else {
+        let data_home = env_var("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .or_else(|| expand_home("~/.local/share").ok());
+        candidates.extend(data_home.map(|d| d.join("fnm")));
+    }
+    // Older fnm releases used ~/.fnm everywhere
+    candidates.extend(expand_home("~/.fnm").ok());
+
+    let Some(fnm_dir) = candidates.into_iter().find(|d| d.join("node-versions").is_dir()) else {
+        return Vec::new();
+    };
+    version_dirs(&fnm_dir.join("node-versions"))
+        .into_iter()
+        .map(|(version, dir)| {
+            let installation = dir.join("installation");
+            let bin = if cfg!(target_os = "windows") {
+                installation
+            } else {
+                installation.join("bin")
+            };
+            node_install("fnm", version, dir, bin)
+        })
+        .collect()
+}
+
+fn volta_home() -> Option<PathBuf> {
+    env_var("VOLTA_HOME").map(PathBuf::from).or_else(|| {
+        match env_var("LOCALAPPDATA").filter(|_| cfg!(target_os = "windows")) {
+            Some(local) => Some(PathBuf::from(local).join("Volta")),
+            None => expand_home("~/.volta").ok(),
+        }
+    })
+}
+
+/// Whether a resolved path lives under the volta home.
+fn in_volta(resolved: &std::path::Path) -> bool {
+    volta_home().is_some_and(|home| resolved.starts_with(fs::canonicalize(&home).unwrap_or(home)))
+}
+
+fn detect_volta() -> Vec<NodeInstall> {
+    let Some(home) = volta_home() else {
+        return Vec::new();
+    };
+    version_dirs(&home.join("tools").join("image").join("node"))
+        .into_iter()
+        .map(|(version, dir)| {
+            let bin = if cfg!(target_os = "windows") {
+                dir.clone()
+            } else {
+                dir.join("bin")
+            };
+            node_install("volta", version, dir, bin)
+        })
+        .collect()
+}
+
+/// The `node` on PATH, unless it belongs to one of the managers above.
+fn detect_system(managed: &[NodeInstall]) -> Option<NodeInstall> {
+    let node = find_program("node")?;
+    let resolved = fs::canonicalize(&node).unwrap_or_else(|_| node.clone());
+    if in_volta(&resolved) || install_containing(&resolved, managed).is_some() {
+        return None;
+    }
+    let version = get_version(&node.to_string_lossy(), &["-v"])?;
+    let bin_dir = node.parent()?.to_path_buf();
+    Some(node_install(
+        "system",
+        version.trim_start_matches('v').to_string(),
+        bin_dir.clone(),
+        bin_dir,
+    ))
+}
+
+/// Last `detect_node_installs` result. Every tool launch and background command
+/// resolves the selected Node through it, and a scan runs `node -v`.
+static NODE_INSTALLS: Mutex<Option<Vec<NodeInstall>>> = Mutex::new(None);
+
+/// All Node installations from nvm, fnm, volta and PATH, newest first per manager.
+/// Cached until `clear_node_installs_cache`.
+pub fn detect_node_installs(app: &AppHandle) -> Vec<NodeInstall> {
+    if let Some(installs) = NODE_INSTALLS.lock().unwrap().as_ref() {
+        return installs.clone();
+    }
+    let installs = scan_node_installs(app);
+    *NODE_INSTALLS.lock().unwrap() = Some(installs.clone());
+    installs
+}
+
+/// Forgets the cached installations, e.g. after the selection changed.
+pub fn clear_node_installs_cache() {
+    *NODE_INSTALLS.lock().unwrap() = None;
+}
+
+fn scan_node_installs(app: &AppHandle) -> Vec<NodeInstall> {
+    let mut installs = Vec::new();
+    for mut found in [detect_nvm(), detect_fnm(), detect_volta()] {
+        found.sort_by_cached_key(|i| std::cmp::Reverse(semver::Version::parse(&i.version).ok()));
+        installs.extend(found);
+    }
+    if let Some(system) = detect_system(&installs) {
+        installs.push(system);
+    }
+
+    let selected = get_app_config(app.clone()).node_install;
+    for install in installs.iter_mut() {
+        install.selected = selected.as_deref() == Some(install.id.as_str());
+    }
+    installs
+}
+
+fn install_containing<'a>(path: &std::path::Path, installs: &'a [NodeInstall]) -> Option<&'a NodeInstall> {
+    installs.iter().find(|i| {
+        let root = PathBuf::from(&i.root);
+        path.starts_with(fs::canonicalize(&root).unwrap_or(root))
+    })
+}
+
+/// Which Node installation a tool binary belongs to, as a `NodeInstall` id, or
+/// "volta" for volta shims (volta picks the Node per package at run time).
+pub fn node_install_for(path: &std::path::Path, installs: &[NodeInstall]) -> Option<String> {
+    let resolved = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    if let Some(install) = install_containing(&resolved, installs) {
+        return Some(install.id.clone());
+    }
+    in_volta(&resolved).then(|| "volta".to_string())
+}
+
+pub fn selected_node_install(app: &AppHandle) -> Option<NodeInstall> {
+    detect_node_installs(app).into_iter().find(|i| i.selected)
+}
+
+/// PATH with the selected Node's bin dir in front, or None to leave PATH alone.
+pub fn node_path(app: &AppHandle) -> Option<std::ffi::OsString> {
+    let install = selected_node_install(app)?;
+    let mut paths = vec![PathBuf::from(install.bin_dir)];
+    if let Some(path) = shell_path() {
+        paths.extend(std::env::split_paths(&path));
+    }
+    std::env::join_paths(paths).ok()
+}
+
+/// Makes a command run against the selected Node installation.
+pub fn apply_node_path(app: &AppHandle, cmd: &mut Command) {
+    if let Some(path) = node_path(app) {
+        cmd.env("PATH", path);
+    }
+}
+
+/// `create_background_command` using the selected Node installation.
+pub fn node_command(app: &AppHandle, program: &str) -> Command {
+    let mut cmd = create_background_command(program);
+    apply_node_path(app, &mut cmd);
+    cmd
+}
+
+#[tauri::command]
+pub fn list_node_installs(app: AppHandle) -> Vec<NodeInstall> {
+    // Explicit listing rescans, so versions installed meanwhile show up
+    clear_node_installs_cache();
+    detect_node_installs(&app)
+}
+
+/// Picks the Node installation used for tool installs and launches; None goes
+/// back to whatever PATH resolves.
+#[tauri::command]
+pub fn select_node_install(app: AppHandle, id: Option<String>) -> Result<(), String> {
+    if let Some(id) = &id {
+        if !detect_node_installs(&app).iter().any(|i| &i.id == id) {
+            return Err(format!("Node installation {} not found", id));
+        }
+    }
+    let mut config = get_app_config(app.clone());
+    config.node_install = id;
+    write_app_config(&app, &config)?;
+    clear_node_installs_cache();
+    Ok(())
+}
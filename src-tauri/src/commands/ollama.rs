@@ -0,0 +1,206 @@
+#![allow(unused_imports)]
+#![allow(dead_code)]
+
+use futures_util::StreamExt;
+use portable_pty::{Child, CommandBuilder, MasterPty, NativePtySystem, PtySize, PtySystem};
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::cmp::min;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+#[cfg(windows)]
+use std::os::windows::process::CommandExt;
+
+use crate::commands::pty::*;
+use crate::commands::env::*;
+use crate::commands::config::*;
+use crate::commands::fs::*;
+use crate::commands::chat::*;
+use crate::commands::skills::*;
+use crate::commands::utils::*;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LocalModel {
+    pub name: String,
+    pub size: Option<u64>,
+    pub digest: Option<String>,
+    pub modified_at: Option<String>,
+    pub parameter_size: Option<String>,
+    pub quantization_level: Option<String>,
+}
+
+pub fn get_local_ai_base_url(app: &AppHandle) -> String {
+    let config = get_app_config(app.clone());
+    config
+        .local_ai_base_url
+        .filter(|u| !u.is_empty())
+        .unwrap_or_else(|| "http://localhost:11434".to_string())
+        .trim_end_matches('/')
+        .to_string()
+}
+
+/// Local servers are reached directly; a configured proxy would not be able to
+/// reach the user's loopback interface anyway.
+pub fn get_local_ai_client(app: &AppHandle, base_url: &str) -> Result<reqwest::Client, String> {
+    let host = reqwest::Url::parse(base_url)
+        .ok()
+        .and_then(|u| u.host_str().map(|h| h.to_string()))
+        .unwrap_or_default();
+
+    if host == "localhost" || host == "127.0.0.1" || host == "[::1]" || host == "::1" {
+        reqwest::Client::builder()
+            .no_proxy()
+            .build()
+            .map_err(|e| format!("Failed to build HTTP client: {}", e))
+    } else {
+        get_proxy_client(app)
+    }
+}
+
+fn ensure_ollama(app: &AppHandle) -> Result<String, String> {
+    let config = get_app_config(app.clone());
+    match config.local_ai_provider.as_deref() {
+        None | Some("") | Some("ollama") => Ok(get_local_ai_base_url(app)),
+        Some(other) => Err(format!("Local AI provider '{}' is not supported", other)),
+    }
+}
+
+#[tauri::command]
+pub async fn ollama_list_models(app: AppHandle) -> Result<Vec<LocalModel>, String> {
+    let base_url = ensure_ollama(&app)?;
+    let client = get_local_ai_client(&app, &base_url)?;
+
+    let res = client
+        .get(format!("{}/api/tags", base_url))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to connect to Ollama at {}: {}", base_url, e))?;
+
+    if !res.status().is_success() {
+        return Err(format!("API Error: {}", res.status()));
+    }
+
+    let body: serde_json::Value = res
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse JSON: {}", e))?;
+
+    let models = body["models"]
+        .as_array()
+        .map(|models| {
+            models
+                .iter()
+                .filter_map(|m| {
+                    Some(LocalModel {
+                        name: m["name"].as_str()?.to_string(),
+                        size: m["size"].as_u64(),
+                        digest: m["digest"].as_str().map(|s| s.to_string()),
+                        modified_at: m["modified_at"].as_str().map(|s| s.to_string()),
+                        parameter_size: m["details"]["parameter_size"]
+                            .as_str()
+                            .map(|s| s.to_string()),
+                        quantization_level: m["details"]["quantization_level"]
+                            .as_str()
+                            .map(|s| s.to_string()),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok(models)
+}
+
+#[tauri::command]
+pub async fn ollama_pull_model(app: AppHandle, model: String) -> Result<String, String> {
+    let base_url = ensure_ollama(&app)?;
+    let client = get_local_ai_client(&app, &base_url)?;
+
+    let res = client
+        .post(format!("{}/api/pull", base_url))
+        .json(&serde_json::json!({ "model": model, "stream": true }))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to connect to Ollama at {}: {}", base_url, e))?;
+
+    if !res.status().is_success() {
+        let status = res.status();
+        let body = res.text().await.unwrap_or_default();
+        return Err(format!("API Error: {} {}", status, body));
+    }
+
+    let mut stream = res.bytes_stream();
+    let mut buffer: Vec<u8> = Vec::new();
+    let mut last_status = String::new();
+
+    while let Some(item) = stream.next().await {
+        let chunk = item.map_err(|e| format!("Error while reading stream: {}", e))?;
+        buffer.extend_from_slice(&chunk);
+
+        for line in drain_lines(&mut buffer) {
+            let event: serde_json::Value = match serde_json::from_str(&line) {
+                Ok(v) => v,
+                Err(_) => continue,
+            };
+
+            if let Some(error) = event["error"].as_str() {
+                return Err(error.to_string());
+            }
+
+            let status = event["status"].as_str().unwrap_or_default().to_string();
+            let total = event["total"].as_u64();
+            let completed = event["completed"].as_u64();
+            let percent = match (total, completed) {
+                (Some(t), Some(c)) if t > 0 => Some((c as f64 / t as f64) * 100.0),
+                _ => None,
+            };
+
+            // Emit event: "ollama-pull-progress" { model: "llama3", status: "pulling ...", percent: 50.5, ... }
+            let _ = app.emit(
+                "ollama-pull-progress",
+                serde_json::json!({
+                    "model": model,
+                    "status": status,
+                    "digest": event["digest"].as_str(),
+                    "total": total,
+                    "completed": completed,
+                    "percent": percent
+                }),
+            );
+            last_status = status;
+        }
+    }
+
+    if last_status == "success" {
+        Ok(format!("Pulled {} successfully", model))
+    } else {
+        Err(format!("Pull of {} ended unexpectedly: {}", model, last_status))
+    }
+}
+
+#[tauri::command]
+pub async fn ollama_delete_model(app: AppHandle, model: String) -> Result<(), String> {
+    let base_url = ensure_ollama(&app)?;
+    let client = get_local_ai_client(&app, &base_url)?;
+
+    let res = client
+        .delete(format!("{}/api/delete", base_url))
+        .json(&serde_json::json!({ "model": model }))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to connect to Ollama at {}: {}", base_url, e))?;
+
+    if res.status().is_success() {
+        Ok(())
+    } else {
+        let status = res.status();
+        let body = res.text().await.unwrap_or_default();
+        Err(format!("API Error: {} {}", status, body))
+    }
+}
@@ -0,0 +1,147 @@
+#![allow(unused_imports)]
+#![allow(dead_code)]
+
+use futures_util::StreamExt;
+use portable_pty::{Child, CommandBuilder, MasterPty, NativePtySystem, PtySize, PtySystem};
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::cmp::min;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+#[cfg(windows)]
+use std::os::windows::process::CommandExt;
+
+use crate::commands::pty::*;
+use crate::commands::env::*;
+use crate::commands::config::*;
+use crate::commands::fs::*;
+use crate::commands::chat::*;
+use crate::commands::skills::*;
+use crate::commands::utils::*;
+
+/// Built-in probes. `AppConfig.environment_probes` adds to or overrides these by name.
+const BUILTIN_PROBES: &str = include_str!("probes.json");
+
+/// One entry of the environment check: the programs to try, in order, and how
+/// to read a version out of their output.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ProbeDefinition {
+    pub name: String,
+    pub label: Option<String>,
+    pub programs: Vec<String>,
+    #[serde(default = "default_probe_args")]
+    pub args: Vec<String>,
+    /// Applied to the first output line; the first capture group (or the whole
+    /// match) becomes the version. Without it the whole line is reported.
+    pub version_regex: Option<String>,
+    /// Lets the config switch off a built-in probe
+    #[serde(default)]
+    pub disabled: bool,
+}
+
+fn default_probe_args() -> Vec<String> {
+    vec!["--version".to_string()]
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+struct ProbeManifest {
+    #[serde(default)]
+    probes: Vec<ProbeDefinition>,
+}
+
+/// Built-in probes merged with the ones in the app config, in definition order.
+pub fn load_probes(app: &AppHandle) -> Vec<ProbeDefinition> {
+    let manifest: ProbeManifest =
+        serde_json::from_str(BUILTIN_PROBES).expect("built-in probes.json is invalid");
+    let mut probes = manifest.probes;
+    for probe in get_app_config(app.clone()).environment_probes.unwrap_or_default() {
+        match probes.iter_mut().find(|p| p.name == probe.name) {
+            Some(existing) => *existing = probe,
+            None => probes.push(probe),
+        }
+    }
+    probes.retain(|p| !p.disabled);
+    probes
+}
+
+fn run_probe(probe: &ProbeDefinition) -> ProbeResult {
+    let programs: Vec<&str> = probe.programs.iter().map(|s| s.as_str()).collect();
+    let args: Vec<&str> = probe.args.iter().map(|s| s.as_str()).collect();
+    let mut result = probe_candidates(&programs, &args, PROBE_TIMEOUT);
+
+    let (Some(pattern), Some(line)) = (&probe.version_regex, &result.version) else {
+        return result;
+    };
+    match regex::Regex::new(pattern) {
+        Ok(re) => {
+            if let Some(caps) = re.captures(line) {
+                let matched = caps.get(1).or_else(|| caps.get(0));
+                result.version = matched.map(|m| m.as_str().to_string());
+            }
+        }
+        Err(e) => {
+            result.status = ProbeStatus::Error;
+            result.error = Some(format!("Invalid version regex for {}: {}", probe.name, e));
+        }
+    }
+    result
+}
+
+/// Runs every probe on its own thread so one slow binary only costs its own timeout.
+pub fn run_probes(probes: &[ProbeDefinition]) -> HashMap<String, ProbeResult> {
+    thread::scope(|scope| {
+        let handles: Vec<_> = probes
+            .iter()
+            .map(|probe| (probe.name.clone(), scope.spawn(move || run_probe(probe))))
+            .collect();
+        handles
+            .into_iter()
+            .map(|(name, handle)| {
+                let result = handle.join().unwrap_or_else(|_| ProbeResult {
+                    status: ProbeStatus::Error,
+                    version: None,
+                    error: Some("Probe panicked".to_string()),
+                });
+                (name, result)
+            })
+            .collect()
+    })
+}
+
+#[tauri::command]
+pub fn list_environment_probes(app: AppHandle) -> Vec<ProbeDefinition> {
+    load_probes(&app)
+}
+
+/// Replaces the probes stored in the app config, which add to or override the
+/// built-in ones by name (`disabled` switches a built-in off). Every entry is
+/// checked first so a bad regex can't land in the config.
+#[tauri::command]
+pub fn save_environment_probes(app: AppHandle, probes: Vec<ProbeDefinition>) -> Result<(), String> {
+    let mut names = std::collections::HashSet::new();
+    for probe in &probes {
+        if probe.name.trim().is_empty() {
+            return Err("Probe name must not be empty".to_string());
+        }
+        if !names.insert(probe.name.as_str()) {
+            return Err(format!("Probe {} is defined twice", probe.name));
+        }
+        if !probe.disabled && probe.programs.iter().all(|p| p.trim().is_empty()) {
+            return Err(format!("Probe {} needs at least one program", probe.name));
+        }
+        if let Some(pattern) = &probe.version_regex {
+            regex::Regex::new(pattern)
+                .map_err(|e| format!("Invalid version regex for {}: {}", probe.name, e))?;
+        }
+    }
+
+    let mut config = get_app_config(app.clone());
+    config.environment_probes = (!probes.is_empty()).then_some(probes);
+    write_app_config(&app, &config)
+}
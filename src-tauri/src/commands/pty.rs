@@ -3,7 +3,7 @@
 #![allow(dead_code)]
 
 use futures_util::StreamExt;
-use portable_pty::{Child, CommandBuilder, MasterPty, NativePtySystem, PtySize, PtySystem};
+use portable_pty::{Child, ChildKiller, CommandBuilder, MasterPty, NativePtySystem, PtySize, PtySystem};
 use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
 use std::cmp::min;
@@ -22,43 +22,186 @@ use crate::commands::env::*;
 use crate::commands::config::*;
 use crate::commands::fs::*;
 use crate::commands::chat::*;
+use crate::commands::recording::*;
+use crate::commands::registry::*;
+use crate::commands::shell_env::*;
 use crate::commands::skills::*;
 use crate::commands::utils::*;
 
+/// Set to None by the exit watcher at the moment it reaps the child, so nothing
+/// signals a PID the OS may already have handed to another process.
+type SharedKiller = Arc<Mutex<Option<Box<dyn ChildKiller + Send + Sync>>>>;
+
+/// How often the exit watcher checks whether the child has exited.
+const PTY_EXIT_POLL: std::time::Duration = std::time::Duration::from_millis(100);
+
 pub struct PtySession {
     writer: Box<dyn Write + Send>,
     master: Box<dyn MasterPty + Send>,
-    child: Box<dyn Child + Send>,
+    // The child itself is owned by the exit watcher thread; we only keep a killer,
+    // which the watcher clears once it has reaped the child
+    killer: SharedKiller,
+    pid: Option<u32>,
+    // Distinguishes sessions that reuse the same id after a close/reopen
+    generation: u64,
+    scrollback: Arc<Mutex<Scrollback>>,
+    recorder: Option<Arc<Mutex<CastRecorder>>>,
+}
+
+/// Upper bound on buffered output kept per session for `pty_attach`.
+pub const PTY_SCROLLBACK_LIMIT: usize = 512 * 1024;
+
+/// Bounded ring buffer of the most recent PTY output.
+#[derive(Default)]
+pub struct Scrollback {
+    buf: std::collections::VecDeque<u8>,
+    // Total bytes ever written, used as the stream offset of the next chunk
+    total: u64,
+}
+
+impl Scrollback {
+    pub fn push(&mut self, data: &[u8]) {
+        self.buf.extend(data);
+        let overflow = self.buf.len().saturating_sub(PTY_SCROLLBACK_LIMIT);
+        if overflow > 0 {
+            self.buf.drain(..overflow);
+        }
+        self.total += data.len() as u64;
+    }
+
+    pub fn snapshot(&self) -> Vec<u8> {
+        self.buf.iter().copied().collect()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PtyAttach {
+    pub id: String,
+    pub data: Vec<u8>,
+    // Stream offset right after `data`; later `pty-data` events start at or beyond it
+    pub offset: u64,
+}
+
+impl PtySession {
+    fn kill(&self) {
+        if let Some(killer) = self.killer.lock().unwrap().as_mut() {
+            let _ = killer.kill();
+        }
+    }
 }
 
 impl Drop for PtySession {
     fn drop(&mut self) {
-        let _ = self.child.kill();
+        self.kill();
     }
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PtyExitStatus {
+    pub exit_code: u32,
+    pub signal: Option<String>,
+    pub success: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PtyStatus {
+    pub id: String,
+    pub running: bool,
+    pub pid: Option<u32>,
+    pub exit: Option<PtyExitStatus>,
+}
+
 pub struct AppPty {
     pub sessions: Arc<Mutex<HashMap<String, PtySession>>>,
+    // Exit status of sessions that have ended, kept until the id is reopened
+    pub exited: Arc<Mutex<HashMap<String, PtyExitStatus>>>,
+    // Cancel flags of recordings being replayed into a terminal, by terminal id
+    pub replays: Arc<Mutex<HashMap<String, Arc<std::sync::atomic::AtomicBool>>>>,
 }
 
 impl Default for AppPty {
     fn default() -> Self {
         Self {
             sessions: Arc::new(Mutex::new(HashMap::new())),
+            exited: Arc::new(Mutex::new(HashMap::new())),
+            replays: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 }
 
+static PTY_GENERATION: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+
 pub fn create_background_command(program: &str) -> Command {
     #[allow(unused_mut)]
     let mut cmd = Command::new(program);
     #[cfg(windows)]
     cmd.creation_flags(0x08000000); // ash::windows::process::CREATE_NO_WINDOW
+    // Desktop-launched apps miss PATH additions from shell rc files; see shell_env
+    if let Some(env) = shell_env() {
+        cmd.envs(env);
+    }
     cmd
 }
 
+/// Per-session overrides for `pty_open`. Anything left unset falls back to the
+/// named profile, then to the global terminal settings in `AppConfig`.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct PtyOpenOptions {
+    pub profile: Option<String>,
+    pub program: Option<String>,
+    pub args: Option<Vec<String>>,
+    pub cwd: Option<String>,
+    pub env: Option<HashMap<String, String>>,
+    pub record: Option<bool>,
+    #[serde(rename = "recordInput")]
+    pub record_input: Option<bool>,
+    /// Registry id of the tool this session runs; applies its `ToolConfig`
+    /// working directory and provider env vars
+    pub tool: Option<String>,
+}
+
+fn resolve_pty_options(config: &AppConfig, options: PtyOpenOptions) -> Result<PtyOpenOptions, String> {
+    let Some(profile_id) = options.profile.as_deref() else {
+        return Ok(options);
+    };
+
+    let profile = config
+        .terminal_profiles
+        .as_ref()
+        .and_then(|profiles| profiles.iter().find(|p| p.id == profile_id))
+        .ok_or_else(|| format!("Terminal profile not found: {}", profile_id))?;
+
+    // Profile env is the base; explicit per-session entries win on conflicts
+    let env = match (&profile.env, options.env) {
+        (Some(base), Some(extra)) => {
+            let mut merged = base.clone();
+            merged.extend(extra);
+            Some(merged)
+        }
+        (base, extra) => extra.or_else(|| base.clone()),
+    };
+
+    Ok(PtyOpenOptions {
+        profile: options.profile.clone(),
+        program: options.program.or_else(|| profile.program.clone()),
+        args: options.args.or_else(|| profile.args.clone()),
+        cwd: options.cwd.or_else(|| profile.cwd.clone()),
+        env,
+        record: options.record,
+        record_input: options.record_input,
+        tool: options.tool,
+    })
+}
+
 #[tauri::command]
-pub fn pty_open(app: AppHandle, state: State<'_, AppPty>, id: String, cols: u16, rows: u16) -> Result<(), String> {
+pub fn pty_open(
+    app: AppHandle,
+    state: State<'_, AppPty>,
+    id: String,
+    cols: u16,
+    rows: u16,
+    options: Option<PtyOpenOptions>,
+) -> Result<(), String> {
     let pty_system = NativePtySystem::default();
 
     let pair = pty_system
@@ -71,7 +214,25 @@ pub fn pty_open(app: AppHandle, state: State<'_, AppPty>, id: String, cols: u16,
         .map_err(|e| e.to_string())?;
 
     let config = get_app_config(app.clone());
-    let cmd_line = config.terminal_shell.clone().unwrap_or_else(|| {
+    let mut options = resolve_pty_options(&config, options.unwrap_or_default())?;
+
+    // A tool session picks up the tool's ToolConfig; explicit options still win
+    let tool_env = match options.tool.as_deref() {
+        Some(tool_id) => {
+            let registry = ToolRegistry::load(&app);
+            let tool = registry
+                .get(tool_id)
+                .ok_or_else(|| format!("Unknown tool: {}", tool_id))?;
+            if options.cwd.is_none() {
+                options.cwd = get_tool_config(&app, tool)
+                    .and_then(|c| c.working_directory)
+                    .filter(|dir| !dir.is_empty());
+            }
+            tool_launch_env(&app, tool)
+        }
+        None => HashMap::new(),
+    };
+    let cmd_line = options.program.clone().or(config.terminal_shell.clone()).unwrap_or_else(|| {
         if cfg!(target_os = "windows") {
             // Try to find Git Bash first
             let git_bash = std::path::Path::new("C:\\Program Files\\Git\\bin\\bash.exe");
@@ -85,7 +246,14 @@ pub fn pty_open(app: AppHandle, state: State<'_, AppPty>, id: String, cols: u16,
         }
     });
 
-    let mut cmd = if cmd_line == "wsl" {
+    let shell_name = cmd_line.clone();
+
+    let mut cmd = if let Some(args) = &options.args {
+        // An explicit program with args (e.g. `claude --continue`) is run as-is
+        let mut builder = CommandBuilder::new(&cmd_line);
+        builder.args(args);
+        builder
+    } else if cmd_line == "wsl" {
         CommandBuilder::new("wsl.exe")
     } else {
         // Special handling for Git Bash (bash.exe) which might not be in PATH
@@ -125,7 +293,12 @@ pub fn pty_open(app: AppHandle, state: State<'_, AppPty>, id: String, cols: u16,
     cmd.env("DISABLE_UPDATE_CHECKER", "true");
     cmd.env("NPM_CONFIG_UPDATE_NOTIFIER", "false");
 
-    if let Some(cwd) = config.current_directory {
+    if let Some(cwd) = &options.cwd {
+        if !std::path::Path::new(cwd).is_dir() {
+            return Err(format!("Working directory does not exist: {}", cwd));
+        }
+        cmd.cwd(cwd);
+    } else if let Some(cwd) = config.current_directory {
         if !cwd.is_empty() {
             if std::path::Path::new(&cwd).exists() {
                 cmd.cwd(cwd);
@@ -166,10 +339,46 @@ pub fn pty_open(app: AppHandle, state: State<'_, AppPty>, id: String, cols: u16,
         }
     }
 
-    let child = pair
+    for (key, value) in &tool_env {
+        cmd.env(key, value);
+    }
+
+    // Per-session env goes last so it can override anything set above
+    if let Some(env) = &options.env {
+        for (key, value) in env {
+            cmd.env(key, value);
+        }
+    }
+
+    // Opt-in asciinema recording, e.g. to audit what an AI CLI did in this terminal
+    let mut recording_path = None;
+    let recorder = if options.record.unwrap_or(false) {
+        let path = new_recording_path(&app, &id)?;
+        println!("Recording terminal {} to {:?}", id, path);
+        let recorder = CastRecorder::create(
+            &path,
+            cols,
+            rows,
+            &id,
+            &shell_name,
+            options.record_input.unwrap_or(false),
+        )?;
+        recording_path = Some(path);
+        Some(Arc::new(Mutex::new(recorder)))
+    } else {
+        None
+    };
+
+    let mut child = pair
         .slave
         .spawn_command(cmd)
-        .map_err(|e| format!("Failed to spawn shell: {}", e))?;
+        .map_err(|e| {
+            // Don't leave a header-only .cast behind for a session that never ran
+            if let Some(path) = &recording_path {
+                let _ = fs::remove_file(path);
+            }
+            format!("Failed to spawn shell: {}", e)
+        })?;
     println!("Shell spawned successfully: {:?}", child);
 
     // Release slave immediately to allow shell to close it when it exits
@@ -179,31 +388,112 @@ pub fn pty_open(app: AppHandle, state: State<'_, AppPty>, id: String, cols: u16,
     let writer = pair.master.take_writer().map_err(|e| e.to_string())?;
 
     // Store session
+    let generation = PTY_GENERATION.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    let scrollback = Arc::new(Mutex::new(Scrollback::default()));
+    let killer: SharedKiller = Arc::new(Mutex::new(Some(child.clone_killer())));
     let session = PtySession {
         writer,
         master: pair.master,
-        child,
+        killer: killer.clone(),
+        pid: child.process_id(),
+        generation,
+        scrollback: scrollback.clone(),
+        recorder: recorder.clone(),
     };
-    
+
+    state.exited.lock().unwrap().remove(&id);
     state.sessions.lock().unwrap().insert(id.clone(), session);
 
     // Spawn thread to read from PTY
     let incoming_id = id.clone();
+    let reader_app = app.clone();
+    let (reader_done_tx, reader_done_rx) = std::sync::mpsc::channel::<()>();
     thread::spawn(move || {
         let mut buf = [0u8; 1024];
         loop {
             match reader.read(&mut buf) {
                 Ok(n) if n > 0 => {
                     let data = &buf[..n];
+                    // Buffer and emit under the same lock so `pty_attach` sees each
+                    // chunk either in its snapshot or as a later event, never both
+                    let mut scrollback = scrollback.lock().unwrap();
                     let payload = serde_json::json!({
                         "id": incoming_id,
-                        "data": data
+                        "data": data,
+                        "offset": scrollback.total
                     });
-                    let _ = app.emit("pty-data", payload);
+                    scrollback.push(data);
+                    if let Some(recorder) = &recorder {
+                        recorder.lock().unwrap().output(data);
+                    }
+                    let _ = reader_app.emit("pty-data", payload);
                 }
                 _ => break,
             }
         }
+        let _ = reader_done_tx.send(());
+    });
+
+    // Spawn thread to wait for the shell to exit, then report and clean up
+    let sessions = state.sessions.clone();
+    let exited = state.exited.clone();
+    thread::spawn(move || {
+        // Reap and clear the killer under one lock so a concurrent `pty_close`
+        // either kills the live child or finds no killer at all
+        let status = loop {
+            {
+                let mut killer = killer.lock().unwrap();
+                match child.try_wait() {
+                    Ok(Some(status)) => {
+                        killer.take();
+                        break Ok(status);
+                    }
+                    Ok(None) => {}
+                    Err(e) => {
+                        killer.take();
+                        break Err(e);
+                    }
+                }
+            }
+            thread::sleep(PTY_EXIT_POLL);
+        };
+        let status = match status {
+            Ok(s) => PtyExitStatus {
+                exit_code: s.exit_code(),
+                signal: s.signal().map(|sig| sig.to_string()),
+                success: s.success(),
+            },
+            Err(e) => {
+                println!("Failed to wait for PTY child {}: {}", id, e);
+                PtyExitStatus {
+                    exit_code: 1,
+                    signal: None,
+                    success: false,
+                }
+            }
+        };
+
+        // Give the reader a moment to flush trailing output before announcing the exit.
+        // Some platforms only signal EOF once the master is dropped, so don't wait forever.
+        let _ = reader_done_rx.recv_timeout(std::time::Duration::from_millis(500));
+
+        {
+            let mut sessions = sessions.lock().unwrap();
+            if sessions.get(&id).map(|s| s.generation) == Some(generation) {
+                sessions.remove(&id);
+                exited.lock().unwrap().insert(id.clone(), status.clone());
+            }
+        }
+
+        let _ = app.emit(
+            "pty-exit",
+            serde_json::json!({
+                "id": id,
+                "exitCode": status.exit_code,
+                "signal": status.signal,
+                "success": status.success
+            }),
+        );
     });
 
     Ok(())
@@ -211,8 +501,12 @@ pub fn pty_open(app: AppHandle, state: State<'_, AppPty>, id: String, cols: u16,
 
 #[tauri::command]
 pub fn pty_close(state: State<'_, AppPty>, id: String) -> Result<(), String> {
-    if let Some(mut session) = state.sessions.lock().unwrap().remove(&id) {
-        let _ = session.child.kill();
+    if let Some(session) = state.sessions.lock().unwrap().remove(&id) {
+        session.kill();
+    }
+    // Closing a terminal also stops a recording being replayed into it
+    if let Some(cancel) = state.replays.lock().unwrap().remove(&id) {
+        cancel.store(true, std::sync::atomic::Ordering::Relaxed);
     }
     Ok(())
 }
@@ -220,6 +514,9 @@ pub fn pty_close(state: State<'_, AppPty>, id: String) -> Result<(), String> {
 #[tauri::command]
 pub fn pty_write(state: State<'_, AppPty>, id: String, data: String) -> Result<(), String> {
     if let Some(session) = state.sessions.lock().unwrap().get_mut(&id) {
+        if let Some(recorder) = &session.recorder {
+            recorder.lock().unwrap().input(&data);
+        }
         write!(session.writer, "{}", data).map_err(|e| e.to_string())?;
         session.writer.flush().map_err(|e| e.to_string())?;
         Ok(())
@@ -239,6 +536,9 @@ pub fn pty_resize(state: State<'_, AppPty>, id: String, cols: u16, rows: u16) ->
                 pixel_height: 0,
             })
             .map_err(|e| e.to_string())?;
+        if let Some(recorder) = &session.recorder {
+            recorder.lock().unwrap().resize(cols, rows);
+        }
     }
     Ok(())
 }
@@ -248,3 +548,52 @@ pub fn pty_exists(state: State<'_, AppPty>, id: String) -> bool {
     state.sessions.lock().unwrap().contains_key(&id)
 }
 
+#[tauri::command]
+pub fn pty_attach(state: State<'_, AppPty>, id: String) -> Result<PtyAttach, String> {
+    let scrollback = match state.sessions.lock().unwrap().get(&id) {
+        Some(session) => session.scrollback.clone(),
+        None => return Err("PTY session not found".to_string()),
+    };
+
+    let scrollback = scrollback.lock().unwrap();
+    Ok(PtyAttach {
+        id,
+        data: scrollback.snapshot(),
+        offset: scrollback.total,
+    })
+}
+
+#[tauri::command]
+pub fn pty_status(state: State<'_, AppPty>, id: String) -> Result<PtyStatus, String> {
+    if let Some(session) = state.sessions.lock().unwrap().get(&id) {
+        return Ok(PtyStatus {
+            id,
+            running: true,
+            pid: session.pid,
+            exit: None,
+        });
+    }
+
+    if let Some(exit) = state.exited.lock().unwrap().get(&id) {
+        return Ok(PtyStatus {
+            id,
+            running: false,
+            pid: None,
+            exit: Some(exit.clone()),
+        });
+    }
+
+    Err("PTY session not found".to_string())
+}
+
+#[tauri::command]
+pub fn get_terminal_profiles(app: AppHandle) -> Vec<TerminalProfile> {
+    get_app_config(app).terminal_profiles.unwrap_or_default()
+}
+
+#[tauri::command]
+pub fn save_terminal_profiles(app: AppHandle, profiles: Vec<TerminalProfile>) -> Result<(), String> {
+    let mut config = get_app_config(app.clone());
+    config.terminal_profiles = Some(profiles);
+    write_app_config(&app, &config)
+}
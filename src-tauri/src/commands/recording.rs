@@ -0,0 +1,307 @@
+#![allow(unused_imports)]
+#![allow(dead_code)]
+
+use futures_util::StreamExt;
+use portable_pty::{Child, CommandBuilder, MasterPty, NativePtySystem, PtySize, PtySystem};
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::cmp::min;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+#[cfg(windows)]
+use std::os::windows::process::CommandExt;
+
+use crate::commands::pty::*;
+use crate::commands::env::*;
+use crate::commands::config::*;
+use crate::commands::fs::*;
+use crate::commands::chat::*;
+use crate::commands::skills::*;
+use crate::commands::utils::*;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RecordingInfo {
+    pub name: String,
+    pub path: String,
+    pub size: u64,
+    pub timestamp: Option<u64>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub title: Option<String>,
+}
+
+/// Writes terminal output (and optionally input) as an asciinema v2 `.cast` file.
+pub struct CastRecorder {
+    file: std::io::BufWriter<fs::File>,
+    start: std::time::Instant,
+    record_input: bool,
+    // Trailing bytes of an incomplete UTF-8 sequence, completed by the next chunk
+    pending_output: Vec<u8>,
+}
+
+impl CastRecorder {
+    pub fn create(
+        path: &std::path::Path,
+        cols: u16,
+        rows: u16,
+        title: &str,
+        shell: &str,
+        record_input: bool,
+    ) -> Result<Self, String> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let file = fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(path)
+            .map_err(|e| format!("Failed to create recording: {}", e))?;
+        let mut recorder = CastRecorder {
+            file: std::io::BufWriter::new(file),
+            start: std::time::Instant::now(),
+            record_input,
+            pending_output: Vec::new(),
+        };
+
+        let header = serde_json::json!({
+            "version": 2,
+            "width": cols,
+            "height": rows,
+            "timestamp": unix_now(),
+            "title": title,
+            "env": { "SHELL": shell, "TERM": "xterm-256color" }
+        });
+        writeln!(recorder.file, "{}", header).map_err(|e| e.to_string())?;
+        recorder.file.flush().map_err(|e| e.to_string())?;
+        Ok(recorder)
+    }
+
+    pub fn output(&mut self, data: &[u8]) {
+        self.pending_output.extend_from_slice(data);
+        let valid = match std::str::from_utf8(&self.pending_output) {
+            Ok(_) => self.pending_output.len(),
+            // Only hold back an incomplete sequence at the very end; invalid bytes are written lossily
+            Err(e) if e.error_len().is_none() => e.valid_up_to(),
+            Err(_) => self.pending_output.len(),
+        };
+        let chunk: Vec<u8> = self.pending_output.drain(..valid).collect();
+        if !chunk.is_empty() {
+            self.event("o", &String::from_utf8_lossy(&chunk));
+        }
+    }
+
+    pub fn input(&mut self, data: &str) {
+        if self.record_input {
+            self.event("i", data);
+        }
+    }
+
+    pub fn resize(&mut self, cols: u16, rows: u16) {
+        self.event("r", &format!("{}x{}", cols, rows));
+    }
+
+    fn event(&mut self, kind: &str, data: &str) {
+        let elapsed = self.start.elapsed().as_secs_f64();
+        let line = serde_json::json!([elapsed, kind, data]);
+        if writeln!(self.file, "{}", line).and_then(|_| self.file.flush()).is_err() {
+            println!("Failed to write terminal recording event");
+        }
+    }
+}
+
+fn unix_millis() -> u128 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or_default()
+}
+
+fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+pub fn get_recordings_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?
+        .join("recordings");
+    Ok(dir)
+}
+
+/// Builds a fresh `.cast` path for a session, e.g. `<AppData>/recordings/term-1-1767225600123.cast`.
+/// A session reopened within the same millisecond gets a `-1`, `-2`... suffix;
+/// `CastRecorder::create` refuses to overwrite in case of a race.
+pub fn new_recording_path(app: &AppHandle, session_id: &str) -> Result<PathBuf, String> {
+    let safe_id: String = session_id
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect();
+    let dir = get_recordings_dir(app)?;
+    let stem = format!("{}-{}", safe_id, unix_millis());
+    let mut path = dir.join(format!("{}.cast", stem));
+    let mut n = 0;
+    while path.exists() {
+        n += 1;
+        path = dir.join(format!("{}-{}.cast", stem, n));
+    }
+    Ok(path)
+}
+
+/// Resolves a recording by file name, rejecting anything that would escape the recordings dir.
+fn resolve_recording(app: &AppHandle, name: &str) -> Result<PathBuf, String> {
+    if name.is_empty() || name.contains('/') || name.contains('\\') || name.contains("..") {
+        return Err("Invalid recording name".to_string());
+    }
+    let path = get_recordings_dir(app)?.join(name);
+    if !path.is_file() {
+        return Err("Recording not found".to_string());
+    }
+    Ok(path)
+}
+
+#[tauri::command]
+pub fn list_recordings(app: AppHandle) -> Result<Vec<RecordingInfo>, String> {
+    use std::io::BufRead;
+
+    let dir = get_recordings_dir(&app)?;
+    let mut recordings = Vec::new();
+    if !dir.exists() {
+        return Ok(recordings);
+    }
+
+    for entry in fs::read_dir(&dir).map_err(|e| e.to_string())?.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("cast") {
+            continue;
+        }
+
+        // Only the header line is needed for the listing
+        let header: serde_json::Value = fs::File::open(&path)
+            .ok()
+            .and_then(|f| std::io::BufReader::new(f).lines().next())
+            .and_then(|l| l.ok())
+            .and_then(|l| serde_json::from_str(&l).ok())
+            .unwrap_or_default();
+
+        recordings.push(RecordingInfo {
+            name: entry.file_name().to_string_lossy().to_string(),
+            path: path.to_string_lossy().to_string(),
+            size: entry.metadata().map(|m| m.len()).unwrap_or(0),
+            timestamp: header["timestamp"].as_u64(),
+            width: header["width"].as_u64().map(|w| w as u32),
+            height: header["height"].as_u64().map(|h| h as u32),
+            title: header["title"].as_str().map(|s| s.to_string()),
+        });
+    }
+
+    // Newest first
+    recordings.sort_by_key(|r| std::cmp::Reverse(r.timestamp));
+    Ok(recordings)
+}
+
+/// Re-emits a recording's output as `pty-data` events for terminal `id`, honouring
+/// the recorded timing. Idle gaps are capped so long pauses don't stall playback.
+/// `pty_close` on the same id, or a new replay into it, stops the replay.
+#[tauri::command]
+pub fn replay_recording(
+    app: AppHandle,
+    state: State<'_, AppPty>,
+    name: String,
+    id: String,
+    speed: Option<f64>,
+) -> Result<(), String> {
+    use std::io::BufRead;
+
+    const MAX_IDLE_SECS: f64 = 2.0;
+    const REPLAY_POLL: std::time::Duration = std::time::Duration::from_millis(100);
+    const SPEED_RANGE: std::ops::RangeInclusive<f64> = 0.1..=100.0;
+
+    let speed = speed.unwrap_or(1.0);
+    if !speed.is_finite() || !SPEED_RANGE.contains(&speed) {
+        return Err(format!(
+            "Replay speed must be between {} and {}",
+            SPEED_RANGE.start(),
+            SPEED_RANGE.end()
+        ));
+    }
+    let path = resolve_recording(&app, &name)?;
+    let file = fs::File::open(&path).map_err(|e| e.to_string())?;
+
+    let cancel = Arc::new(std::sync::atomic::AtomicBool::new(false));
+    if let Some(previous) = state.replays.lock().unwrap().insert(id.clone(), cancel.clone()) {
+        previous.store(true, std::sync::atomic::Ordering::Relaxed);
+    }
+    let replays = state.replays.clone();
+
+    thread::spawn(move || {
+        let is_cancelled = || cancel.load(std::sync::atomic::Ordering::Relaxed);
+        let mut last = 0.0_f64;
+        // Same stream offsets as live `pty-data`, so the terminal's attach dedupe works
+        let mut offset = 0_u64;
+        // Skip the header line
+        for line in std::io::BufReader::new(file).lines().skip(1).map_while(Result::ok) {
+            let event: serde_json::Value = match serde_json::from_str(&line) {
+                Ok(v) => v,
+                Err(_) => continue,
+            };
+            if event[1] != "o" {
+                continue;
+            }
+            let time = event[0].as_f64().unwrap_or(last);
+            let data = event[2].as_str().unwrap_or_default();
+
+            let delay = (time - last).clamp(0.0, MAX_IDLE_SECS) / speed;
+            last = time;
+            let max_delay = std::time::Duration::from_secs_f64(MAX_IDLE_SECS / SPEED_RANGE.start());
+            // Sleep in slices so a cancel doesn't wait out a long (slowed-down) gap
+            let wake = std::time::Instant::now()
+                + std::time::Duration::try_from_secs_f64(delay).unwrap_or(max_delay);
+            while !is_cancelled() && std::time::Instant::now() < wake {
+                thread::sleep(wake.saturating_duration_since(std::time::Instant::now()).min(REPLAY_POLL));
+            }
+            if is_cancelled() {
+                break;
+            }
+
+            let _ = app.emit(
+                "pty-data",
+                serde_json::json!({
+                    "id": id,
+                    "data": data.as_bytes(),
+                    "offset": offset
+                }),
+            );
+            offset += data.len() as u64;
+        }
+
+        {
+            // Only drop our own flag; a newer replay into the same terminal may own the slot
+            let mut replays = replays.lock().unwrap();
+            if replays.get(&id).is_some_and(|flag| Arc::ptr_eq(flag, &cancel)) {
+                replays.remove(&id);
+            }
+        }
+        let _ = app.emit(
+            "pty-replay-done",
+            serde_json::json!({ "id": id, "name": name, "cancelled": is_cancelled() }),
+        );
+    });
+
+    Ok(())
+}
+
+#[tauri::command]
+pub fn delete_recording(app: AppHandle, name: String) -> Result<(), String> {
+    let path = resolve_recording(&app, &name)?;
+    fs::remove_file(path).map_err(|e| e.to_string())
+}
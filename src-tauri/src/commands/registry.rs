@@ -0,0 +1,201 @@
+#![allow(unused_imports)]
+#![allow(dead_code)]
+
+use futures_util::StreamExt;
+use portable_pty::{Child, CommandBuilder, MasterPty, NativePtySystem, PtySize, PtySystem};
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::cmp::min;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+#[cfg(windows)]
+use std::os::windows::process::CommandExt;
+
+use crate::commands::pty::*;
+use crate::commands::env::*;
+use crate::commands::config::*;
+use crate::commands::config_file::*;
+use crate::commands::fs::*;
+use crate::commands::chat::*;
+use crate::commands::installer::*;
+use crate::commands::skills::*;
+use crate::commands::utils::*;
+
+/// Built-in tool manifest. Users can add or override entries in `tools.json` or
+/// `tools.toml` next to `config.json`.
+const BUILTIN_TOOLS: &str = include_str!("tools.json");
+
+/// Skills directory used for targets that aren't in the registry.
+const DEFAULT_SKILLS_DIR: &str = "~/.agents/skills";
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ToolDefinition {
+    pub id: String,
+    pub name: Option<String>,
+    pub program: String,
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    #[serde(default = "default_version_args")]
+    pub version_args: Vec<String>,
+    pub npm_package: Option<String>,
+    #[serde(default)]
+    pub installers: Vec<InstallMethod>,
+    #[serde(default)]
+    pub llm_env: LlmEnv,
+    pub skills_dir: Option<String>,
+}
+
+/// Environment variables a tool reads its provider settings from. The matching
+/// `ToolConfig` values are exported under each listed name at launch.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct LlmEnv {
+    #[serde(default)]
+    pub api_key: Vec<String>,
+    #[serde(default)]
+    pub base_url: Vec<String>,
+    #[serde(default)]
+    pub model: Vec<String>,
+}
+
+fn default_version_args() -> Vec<String> {
+    vec!["--version".to_string()]
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ToolManifest {
+    #[serde(default)]
+    pub tools: Vec<ToolDefinition>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ToolRegistry {
+    pub tools: Vec<ToolDefinition>,
+}
+
+impl ToolDefinition {
+    /// Program names to probe for a version. npm and pnpm install shims as `.cmd` on Windows.
+    pub fn status_programs(&self) -> Vec<String> {
+        if cfg!(target_os = "windows") && self.npm_package.is_some() {
+            vec![format!("{}.cmd", self.program), self.program.clone()]
+        } else {
+            vec![self.program.clone()]
+        }
+    }
+
+    /// Install methods in order of preference. An `npm_package` implies npm, with
+    /// pnpm and bun as fallbacks for machines that only have one of those.
+    pub fn installers(&self) -> Vec<InstallMethod> {
+        let mut methods = Vec::new();
+        if let Some(package) = &self.npm_package {
+            methods.push(InstallMethod::Npm { package: package.clone() });
+            methods.push(InstallMethod::Pnpm { package: package.clone() });
+            methods.push(InstallMethod::Bun { package: package.clone() });
+        }
+        for method in &self.installers {
+            if !methods.iter().any(|m| m.name() == method.name()) {
+                methods.push(method.clone());
+            }
+        }
+        methods
+    }
+
+    pub fn skills_path(&self) -> Option<Result<PathBuf, String>> {
+        self.skills_dir.as_deref().map(expand_home)
+    }
+}
+
+impl ToolRegistry {
+    /// Loads the built-in manifest, then applies the user's manifests on top
+    /// (`tools.json`, then `tools.toml`). User entries with an existing id replace
+    /// the built-in definition.
+    pub fn load(app: &AppHandle) -> Self {
+        let mut registry = Self::builtin();
+        for user_path in get_user_tools_paths(app) {
+            if !user_path.exists() {
+                continue;
+            }
+            match read_tool_manifest(&user_path) {
+                Ok(manifest) => registry.merge(manifest),
+                Err(e) => println!("Ignoring invalid tool manifest {:?}: {}", user_path, e),
+            }
+        }
+        registry
+    }
+
+    pub fn builtin() -> Self {
+        let manifest: ToolManifest =
+            serde_json::from_str(BUILTIN_TOOLS).expect("built-in tools.json is invalid");
+        Self {
+            tools: manifest.tools,
+        }
+    }
+
+    pub fn merge(&mut self, manifest: ToolManifest) {
+        for tool in manifest.tools {
+            match self.tools.iter_mut().find(|t| t.id == tool.id) {
+                Some(existing) => *existing = tool,
+                None => self.tools.push(tool),
+            }
+        }
+    }
+
+    /// Looks a tool up by id or alias, case-insensitively (skills targets use e.g. "gemini").
+    pub fn get(&self, id: &str) -> Option<&ToolDefinition> {
+        let id = id.to_lowercase();
+        self.tools.iter().find(|t| {
+            t.id.to_lowercase() == id || t.aliases.iter().any(|a| a.to_lowercase() == id)
+        })
+    }
+
+    pub fn skills_dir(&self, target: &str) -> Result<PathBuf, String> {
+        self.get(target)
+            .and_then(|t| t.skills_path())
+            .unwrap_or_else(|| expand_home(DEFAULT_SKILLS_DIR))
+    }
+}
+
+pub fn get_user_tools_paths(app: &AppHandle) -> Vec<PathBuf> {
+    let config_path = get_config_path(app);
+    vec![
+        config_path.with_file_name("tools.json"),
+        config_path.with_file_name("tools.toml"),
+    ]
+}
+
+/// Parses a user manifest as JSON or TOML, by extension.
+fn read_tool_manifest(path: &std::path::Path) -> Result<ToolManifest, String> {
+    let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let format = ConfigFormat::resolve(None, path)?;
+    let value = parse_config(format, &content)
+        .map_err(|e| e.to_string())?
+        .to_json()?;
+    serde_json::from_value(value).map_err(|e| e.to_string())
+}
+
+/// Expands a leading `~` to the user's home directory. `~user` forms are left as is.
+pub fn expand_home(path: &str) -> Result<PathBuf, String> {
+    let Some(rest) = path.strip_prefix('~') else {
+        return Ok(PathBuf::from(path));
+    };
+    if !rest.is_empty() && !rest.starts_with(['/', '\\']) {
+        return Ok(PathBuf::from(path));
+    }
+    // An empty home would silently turn "~/.claude/skills" into a relative path
+    let home = std::env::var("HOME")
+        .or_else(|_| std::env::var("USERPROFILE"))
+        .ok()
+        .filter(|h| !h.is_empty())
+        .ok_or_else(|| format!("Cannot expand {}: home directory is not set", path))?;
+    Ok(PathBuf::from(home).join(rest.trim_start_matches(['/', '\\'])))
+}
+
+#[tauri::command]
+pub fn list_tools(app: AppHandle) -> Vec<ToolDefinition> {
+    ToolRegistry::load(&app).tools
+}
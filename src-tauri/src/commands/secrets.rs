@@ -0,0 +1,422 @@
+#![allow(unused_imports)]
+#![allow(dead_code)]
+
+use futures_util::StreamExt;
+use portable_pty::{Child, CommandBuilder, MasterPty, NativePtySystem, PtySize, PtySystem};
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::cmp::min;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+#[cfg(windows)]
+use std::os::windows::process::CommandExt;
+
+use crate::commands::pty::*;
+use crate::commands::env::*;
+use crate::commands::config::*;
+use crate::commands::fs::*;
+use crate::commands::chat::*;
+use crate::commands::skills::*;
+use crate::commands::utils::*;
+
+/// Service name secrets are filed under in the OS keyring.
+const KEYRING_SERVICE: &str = "openvizui";
+
+/// Set to "file" to skip the OS keyring, e.g. on headless machines or in tests.
+const SECRET_STORE_ENV: &str = "OPENVIZUI_SECRET_STORE";
+
+/// Where API keys and other credentials live. `AppConfig` only ever holds the
+/// ids they are stored under.
+pub trait SecretStore: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn get(&self, id: &str) -> Result<Option<String>, String>;
+    fn set(&self, id: &str, value: &str) -> Result<(), String>;
+    fn delete(&self, id: &str) -> Result<(), String>;
+}
+
+/// The platform keyring: Secret Service on Linux, Keychain on macOS, Credential
+/// Manager on Windows.
+pub struct KeyringStore;
+
+impl KeyringStore {
+    fn entry(id: &str) -> Result<keyring::Entry, String> {
+        keyring::Entry::new(KEYRING_SERVICE, id).map_err(|e| e.to_string())
+    }
+
+    /// Whether a keyring backend is actually reachable; a missing Secret Service
+    /// daemon only shows up once something is looked up.
+    fn is_available() -> bool {
+        match Self::entry("__probe__").and_then(|e| match e.get_password() {
+            Ok(_) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(e.to_string()),
+        }) {
+            Ok(()) => true,
+            Err(e) => {
+                println!("OS keyring unavailable, using encrypted file: {}", e);
+                false
+            }
+        }
+    }
+}
+
+impl SecretStore for KeyringStore {
+    fn name(&self) -> &'static str {
+        "keyring"
+    }
+
+    fn get(&self, id: &str) -> Result<Option<String>, String> {
+        match Self::entry(id)?.get_password() {
+            Ok(value) => Ok(Some(value)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
+    fn set(&self, id: &str, value: &str) -> Result<(), String> {
+        Self::entry(id)?.set_password(value).map_err(|e| e.to_string())
+    }
+
+    fn delete(&self, id: &str) -> Result<(), String> {
+        match Self::entry(id)?.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+}
+
+/// Fallback for machines without a keyring: a ChaCha20-Poly1305 encrypted JSON
+/// map, with the key in a separate owner-only file. This keeps secrets out of
+/// `config.json` and its backups, though anyone who can read both files as the
+/// user can decrypt them.
+pub struct EncryptedFileStore {
+    path: PathBuf,
+    key_path: PathBuf,
+    lock: Mutex<()>,
+}
+
+impl EncryptedFileStore {
+    pub fn new(dir: &std::path::Path) -> Self {
+        EncryptedFileStore {
+            path: dir.join("secrets.enc"),
+            key_path: dir.join("secrets.key"),
+            lock: Mutex::new(()),
+        }
+    }
+
+    fn cipher(&self) -> Result<chacha20poly1305::ChaCha20Poly1305, String> {
+        use chacha20poly1305::aead::{KeyInit, OsRng};
+        use chacha20poly1305::ChaCha20Poly1305;
+
+        if let Ok(key) = fs::read(&self.key_path) {
+            return ChaCha20Poly1305::new_from_slice(&key)
+                .map_err(|_| format!("Invalid secrets key file {:?}", self.key_path));
+        }
+        // A fresh key could never decrypt the existing file, and the next save
+        // would silently replace every stored secret
+        if self.path.exists() {
+            return Err(format!(
+                "Secrets key file {:?} is missing, so {:?} can't be decrypted. Restore the key file, or delete the secrets file to start over.",
+                self.key_path, self.path
+            ));
+        }
+
+        let key = ChaCha20Poly1305::generate_key(&mut OsRng);
+        if let Some(dir) = self.key_path.parent() {
+            fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut file = options.open(&self.key_path).map_err(|e| e.to_string())?;
+        file.write_all(&key).map_err(|e| e.to_string())?;
+        file.sync_all().map_err(|e| e.to_string())?;
+        Ok(ChaCha20Poly1305::new(&key))
+    }
+
+    fn load(&self) -> Result<HashMap<String, String>, String> {
+        use chacha20poly1305::aead::Aead;
+
+        let Ok(data) = fs::read(&self.path) else {
+            return Ok(HashMap::new());
+        };
+        // 12-byte nonce followed by the ciphertext
+        if data.len() < 12 {
+            return Err(format!("Secrets file {:?} is truncated", self.path));
+        }
+        let (nonce, ciphertext) = data.split_at(12);
+        let plaintext = self
+            .cipher()?
+            .decrypt(chacha20poly1305::Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| format!("Failed to decrypt {:?}", self.path))?;
+        serde_json::from_slice(&plaintext).map_err(|e| e.to_string())
+    }
+
+    fn save(&self, secrets: &HashMap<String, String>) -> Result<(), String> {
+        use chacha20poly1305::aead::{Aead, AeadCore, OsRng};
+
+        let plaintext = serde_json::to_vec(secrets).map_err(|e| e.to_string())?;
+        let nonce = chacha20poly1305::ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher()?
+            .encrypt(&nonce, plaintext.as_slice())
+            .map_err(|_| "Failed to encrypt secrets".to_string())?;
+
+        let mut data = nonce.to_vec();
+        data.extend(ciphertext);
+        atomic_write(&self.path, &data)
+    }
+}
+
+impl SecretStore for EncryptedFileStore {
+    fn name(&self) -> &'static str {
+        "file"
+    }
+
+    fn get(&self, id: &str) -> Result<Option<String>, String> {
+        let _guard = self.lock.lock().unwrap();
+        Ok(self.load()?.remove(id))
+    }
+
+    fn set(&self, id: &str, value: &str) -> Result<(), String> {
+        let _guard = self.lock.lock().unwrap();
+        let mut secrets = self.load()?;
+        secrets.insert(id.to_string(), value.to_string());
+        self.save(&secrets)
+    }
+
+    fn delete(&self, id: &str) -> Result<(), String> {
+        let _guard = self.lock.lock().unwrap();
+        let mut secrets = self.load()?;
+        if secrets.remove(id).is_some() {
+            self.save(&secrets)?;
+        }
+        Ok(())
+    }
+}
+
+static SECRET_STORE: std::sync::OnceLock<Box<dyn SecretStore>> = std::sync::OnceLock::new();
+
+/// The OS keyring when one is reachable, else the encrypted file in the app data dir.
+pub fn get_secret_store(app: &AppHandle) -> &'static dyn SecretStore {
+    SECRET_STORE
+        .get_or_init(|| {
+            let force_file = std::env::var(SECRET_STORE_ENV).map(|v| v == "file").unwrap_or(false);
+            if !force_file && KeyringStore::is_available() {
+                return Box::new(KeyringStore);
+            }
+            let dir = app
+                .path()
+                .app_data_dir()
+                .unwrap_or_else(|_| get_config_dir(app));
+            Box::new(EncryptedFileStore::new(&dir))
+        })
+        .as_ref()
+}
+
+/// Id the API key of a `tool_configs` entry is stored under.
+pub fn tool_api_key_id(tool_key: &str) -> String {
+    format!("tool_configs.{}.llm_api_key", tool_key)
+}
+
+/// Looks up a secret by id; a missing id or secret resolves to None.
+pub fn resolve_secret(app: &AppHandle, id: Option<&str>) -> Result<Option<String>, String> {
+    match id.filter(|id| !id.is_empty()) {
+        Some(id) => get_secret_store(app).get(id),
+        None => Ok(None),
+    }
+}
+
+/// The endpoint a request goes to, without trailing slashes; empty means the
+/// provider's default.
+fn normalize_base_url(base_url: Option<&str>, api_type: Option<&str>) -> String {
+    base_url
+        .map(str::trim)
+        .filter(|url| !url.is_empty())
+        .unwrap_or_else(|| ChatProvider::from_api_type(api_type).default_base_url())
+        .trim_end_matches('/')
+        .to_string()
+}
+
+/// A stored key is only sent to the base URL of the tool config it was saved
+/// for, so the renderer can't pair a key id with some other host.
+fn check_api_key_target(app: &AppHandle, id: &str, base_url: &str, api_type: Option<&str>) -> Result<(), String> {
+    let tool_config = get_app_config(app.clone())
+        .tool_configs
+        .unwrap_or_default()
+        .into_values()
+        .find(|config| config.llm_api_key_id.as_deref() == Some(id))
+        .ok_or_else(|| format!("API key {} is not used by any tool config", id))?;
+    let stored = normalize_base_url(tool_config.llm_base_url.as_deref(), tool_config.llm_api_type.as_deref());
+    if normalize_base_url(Some(base_url), api_type) != stored {
+        return Err(format!("API key {} is only sent to {}; save the new base URL first", id, stored));
+    }
+    Ok(())
+}
+
+/// An explicit key from the renderer (e.g. typed into a form but not saved yet)
+/// wins over the stored one, which must belong to `base_url`. Keyring lookups
+/// block on D-Bus/IPC, so they run off the async runtime.
+pub async fn resolve_api_key(
+    app: &AppHandle,
+    api_key: Option<String>,
+    api_key_id: Option<String>,
+    base_url: &str,
+    api_type: Option<&str>,
+) -> Result<String, String> {
+    if let Some(key) = api_key.filter(|k| !k.is_empty()) {
+        return Ok(key);
+    }
+    let Some(id) = api_key_id.filter(|id| !id.is_empty()) else {
+        return Ok(String::new());
+    };
+    let app = app.clone();
+    let base_url = base_url.to_string();
+    let api_type = api_type.map(str::to_string);
+    tauri::async_runtime::spawn_blocking(move || {
+        check_api_key_target(&app, &id, &base_url, api_type.as_deref())?;
+        resolve_secret(&app, Some(&id))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map(|key| key.unwrap_or_default())
+}
+
+/// Moves plaintext API keys out of tool configs into the secret store, leaving
+/// only their ids behind. Returns whether anything moved.
+pub fn store_tool_secrets(app: &AppHandle, config: &mut AppConfig) -> Result<bool, String> {
+    let Some(tool_configs) = config.tool_configs.as_mut() else {
+        return Ok(false);
+    };
+    let mut moved = false;
+    for (key, tool_config) in tool_configs.iter_mut() {
+        let Some(api_key) = tool_config.llm_api_key.take() else {
+            continue;
+        };
+        // An empty key from the renderer means "unchanged", not "delete"
+        if api_key.is_empty() {
+            continue;
+        }
+        let id = tool_api_key_id(key);
+        // Skip the write (and a possible keychain prompt) when nothing changed
+        let store = get_secret_store(app);
+        if store.get(&id)?.as_deref() != Some(api_key.as_str()) {
+            store.set(&id, &api_key)?;
+        }
+        tool_config.llm_api_key_id = Some(id);
+        moved = true;
+    }
+    Ok(moved)
+}
+
+/// One-time move of API keys saved in plaintext by older versions.
+pub fn migrate_plaintext_secrets(app: &AppHandle) {
+    let mut config = get_app_config(app.clone());
+    match store_tool_secrets(app, &mut config) {
+        Ok(true) => {
+            println!("Moved plaintext API keys to the {} secret store", get_secret_store(app).name());
+            if let Err(e) = write_app_config(app, &config) {
+                println!("Failed to write config after moving API keys: {}", e);
+            }
+        }
+        Ok(false) => {}
+        Err(e) => println!("Failed to move API keys out of config: {}", e),
+    }
+}
+
+#[tauri::command]
+pub fn set_secret(app: AppHandle, id: String, value: String) -> Result<(), String> {
+    get_secret_store(&app).set(&id, &value)
+}
+
+#[tauri::command]
+pub fn delete_secret(app: AppHandle, id: String) -> Result<(), String> {
+    get_secret_store(&app).delete(&id)
+}
+
+/// The renderer can check a secret is set, but never read it back.
+#[tauri::command]
+pub fn has_secret(app: AppHandle, id: String) -> Result<bool, String> {
+    Ok(get_secret_store(&app).get(&id)?.is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_store_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = EncryptedFileStore::new(dir.path());
+
+        assert_eq!(store.get("a").unwrap(), None);
+        store.set("a", "secret-a").unwrap();
+        store.set("b", "secret-b").unwrap();
+        assert_eq!(store.get("a").unwrap().as_deref(), Some("secret-a"));
+
+        store.delete("a").unwrap();
+        assert_eq!(store.get("a").unwrap(), None);
+        assert_eq!(store.get("b").unwrap().as_deref(), Some("secret-b"));
+        // Deleting a missing id is not an error
+        store.delete("a").unwrap();
+    }
+
+    #[test]
+    fn file_store_persists_across_instances() {
+        let dir = tempfile::tempdir().unwrap();
+        EncryptedFileStore::new(dir.path()).set("a", "secret-a").unwrap();
+
+        let reopened = EncryptedFileStore::new(dir.path());
+        assert_eq!(reopened.get("a").unwrap().as_deref(), Some("secret-a"));
+        // The value isn't stored in the clear
+        let raw = fs::read(dir.path().join("secrets.enc")).unwrap();
+        assert!(!raw.windows(8).any(|w| w == b"secret-a"));
+    }
+
+    #[test]
+    fn file_store_rejects_truncated_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = EncryptedFileStore::new(dir.path());
+        store.set("a", "secret-a").unwrap();
+        fs::write(dir.path().join("secrets.enc"), [0u8; 5]).unwrap();
+
+        let err = store.get("a").unwrap_err();
+        assert!(err.contains("truncated"), "{}", err);
+        assert!(store.set("b", "secret-b").is_err());
+    }
+
+    #[test]
+    fn file_store_refuses_to_replace_key_of_existing_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = EncryptedFileStore::new(dir.path());
+        store.set("a", "secret-a").unwrap();
+        fs::remove_file(dir.path().join("secrets.key")).unwrap();
+
+        let err = store.get("a").unwrap_err();
+        assert!(err.contains("missing"), "{}", err);
+        assert!(store.set("b", "secret-b").is_err());
+        assert!(!dir.path().join("secrets.key").exists());
+    }
+
+    #[test]
+    fn base_url_defaults_and_trailing_slashes_compare_equal() {
+        assert_eq!(
+            normalize_base_url(Some("https://api.openai.com/v1/"), None),
+            normalize_base_url(Some(""), Some("openai"))
+        );
+        assert_eq!(normalize_base_url(None, Some("anthropic")), "https://api.anthropic.com/v1");
+        assert_ne!(
+            normalize_base_url(Some("https://evil.example/v1"), None),
+            normalize_base_url(None, None)
+        );
+    }
+}
@@ -0,0 +1,148 @@
+#![allow(unused_imports)]
+#![allow(dead_code)]
+
+use futures_util::StreamExt;
+use portable_pty::{Child, CommandBuilder, MasterPty, NativePtySystem, PtySize, PtySystem};
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::cmp::min;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+#[cfg(windows)]
+use std::os::windows::process::CommandExt;
+
+use crate::commands::pty::*;
+use crate::commands::env::*;
+use crate::commands::config::*;
+use crate::commands::fs::*;
+use crate::commands::chat::*;
+use crate::commands::skills::*;
+use crate::commands::utils::*;
+
+/// Brackets the `env` dump so rc-file chatter before or after it is ignored.
+const ENV_MARKER: &str = "__OPENVIZUI_SHELL_ENV__";
+
+/// Upper bound for sourcing the user's rc files.
+const CAPTURE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Variables describing the capturing shell itself rather than the user's setup.
+const SKIPPED_VARS: &[&str] = &["PWD", "OLDPWD", "SHLVL", "_"];
+
+static CONFIGURED_SHELL: std::sync::OnceLock<Option<String>> = std::sync::OnceLock::new();
+static SHELL_ENV: std::sync::OnceLock<Option<HashMap<String, String>>> = std::sync::OnceLock::new();
+
+/// Starts capturing the login-shell environment in the background. Apps started
+/// from a desktop launcher on Linux and macOS don't see PATH additions made in
+/// shell rc files (nvm, fnm, asdf, volta, ~/.local/bin), so background commands
+/// use this environment instead of the one the app inherited.
+pub fn init_shell_env(app: &AppHandle) {
+    if cfg!(target_os = "windows") {
+        return;
+    }
+    let _ = CONFIGURED_SHELL.set(get_app_config(app.clone()).terminal_shell);
+    thread::spawn(|| {
+        if let Some(env) = shell_env() {
+            println!("Captured login shell environment ({} variables)", env.len());
+        }
+    });
+}
+
+/// The captured environment, waiting for the capture if it's still running.
+/// None on Windows or if the shell couldn't be run.
+pub fn shell_env() -> Option<&'static HashMap<String, String>> {
+    if cfg!(target_os = "windows") {
+        return None;
+    }
+    SHELL_ENV
+        .get_or_init(|| {
+            let shell = resolve_shell();
+            let env = capture_login_env(&shell);
+            if env.is_none() {
+                println!("Could not capture the environment of {}; using the inherited one", shell);
+            }
+            env
+        })
+        .as_ref()
+}
+
+/// PATH that background commands see.
+pub fn shell_path() -> Option<std::ffi::OsString> {
+    shell_env()
+        .and_then(|env| env.get("PATH"))
+        .map(std::ffi::OsString::from)
+        .or_else(|| std::env::var_os("PATH"))
+}
+
+/// Like `which::which`, but searching the login-shell PATH.
+pub fn find_program(name: &str) -> Option<PathBuf> {
+    find_all_programs(name).into_iter().next()
+}
+
+/// Every match for `name` on the login-shell PATH, in PATH order.
+pub fn find_all_programs(name: &str) -> Vec<PathBuf> {
+    which::which_in_global(name, shell_path())
+        .map(|paths| paths.collect())
+        .unwrap_or_default()
+}
+
+/// The configured terminal shell if it's a POSIX-style shell, else the user's $SHELL.
+fn resolve_shell() -> String {
+    let configured = CONFIGURED_SHELL.get().cloned().flatten();
+    let is_posix = |shell: &str| {
+        let name = std::path::Path::new(shell)
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("");
+        matches!(name, "bash" | "zsh" | "fish" | "sh" | "dash" | "ksh")
+    };
+    configured
+        .filter(|s| is_posix(s))
+        .or_else(|| std::env::var("SHELL").ok().filter(|s| is_posix(s)))
+        .unwrap_or_else(|| "/bin/sh".to_string())
+}
+
+/// Runs `env` in an interactive login shell so both profile and rc files are sourced.
+fn capture_login_env(shell: &str) -> Option<HashMap<String, String>> {
+    let script = format!("printf '%s' {m}; env; printf '%s' {m}", m = ENV_MARKER);
+    let output = output_with_timeout(
+        Command::new(shell).args(["-l", "-i", "-c", &script]),
+        CAPTURE_TIMEOUT,
+    )
+    .ok()??;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let start = stdout.find(ENV_MARKER)? + ENV_MARKER.len();
+    let end = start + stdout[start..].find(ENV_MARKER)?;
+    let env = parse_env(&stdout[start..end]);
+    env.contains_key("PATH").then_some(env)
+}
+
+/// Parses `env` output. Lines that don't start a new NAME= belong to the previous
+/// value, which covers multi-line variables.
+fn parse_env(output: &str) -> HashMap<String, String> {
+    let mut env: Vec<(String, String)> = Vec::new();
+    for line in output.lines() {
+        let new_var = line.split_once('=').filter(|(name, _)| {
+            !name.is_empty()
+                && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+                && !name.starts_with(|c: char| c.is_ascii_digit())
+        });
+        match (new_var, env.last_mut()) {
+            (Some((name, value)), _) => env.push((name.to_string(), value.to_string())),
+            (None, Some((_, value))) => {
+                value.push('\n');
+                value.push_str(line);
+            }
+            (None, None) => {}
+        }
+    }
+    env.into_iter()
+        .filter(|(name, _)| !SKIPPED_VARS.contains(&name.as_str()))
+        .collect()
+}
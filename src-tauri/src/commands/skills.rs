@@ -23,6 +23,7 @@ use crate::commands::env::*;
 use crate::commands::config::*;
 use crate::commands::fs::*;
 use crate::commands::chat::*;
+use crate::commands::registry::*;
 use crate::commands::utils::*;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -38,24 +39,10 @@ pub struct McpToolInfo {
 }
 
 #[tauri::command]
-pub fn list_installed_skills(target: String) -> Vec<McpInfo> {
+pub fn list_installed_skills(app: AppHandle, target: String) -> Vec<McpInfo> {
     let mut mcps = Vec::new();
-    let home = std::env::var("HOME")
-        .or_else(|_| std::env::var("USERPROFILE"))
-        .unwrap_or_default();
-
-    let base_path = match target.to_lowercase().as_str() {
-        "claude" => PathBuf::from(&home).join(".claude").join("skills"),
-        "gemini" | "google" => PathBuf::from(&home).join(".gemini").join("skills"),
-        "opencode" => PathBuf::from(&home)
-            .join(".config")
-            .join("opencode")
-            .join("skills"),
-        "qoder" => PathBuf::from(&home).join(".qoder").join("skills"),
-        "codebuddy" => PathBuf::from(&home).join(".codebuddy").join("skills"),
-        "copilot" => PathBuf::from(&home).join(".copilot").join("skills"),
-        "codex" => PathBuf::from(&home).join(".codex").join("skills"),
-        _ => PathBuf::from(&home).join(".agents").join("skills"),
+    let Ok(base_path) = ToolRegistry::load(&app).skills_dir(&target) else {
+        return mcps;
     };
 
     if base_path.exists() {
@@ -79,28 +66,12 @@ pub fn list_installed_skills(target: String) -> Vec<McpInfo> {
 
 #[tauri::command]
 pub fn install_skills(
-    _app: AppHandle,
+    app: AppHandle,
     url: String,
     name: Option<String>,
     target: String,
 ) -> Result<String, String> {
-    let home = std::env::var("HOME")
-        .or_else(|_| std::env::var("USERPROFILE"))
-        .map_err(|_| "Could not determine home directory".to_string())?;
-
-    let target_base = match target.to_lowercase().as_str() {
-        "claude" => PathBuf::from(&home).join(".claude").join("skills"),
-        "gemini" | "google" => PathBuf::from(&home).join(".gemini").join("skills"),
-        "opencode" => PathBuf::from(&home)
-            .join(".config")
-            .join("opencode")
-            .join("skills"),
-        "qoder" => PathBuf::from(&home).join(".qoder").join("skills"),
-        "codebuddy" => PathBuf::from(&home).join(".codebuddy").join("skills"),
-        "copilot" => PathBuf::from(&home).join(".copilot").join("skills"),
-        "codex" => PathBuf::from(&home).join(".codex").join("skills"),
-        _ => PathBuf::from(&home).join(".agents").join("skills"),
-    };
+    let target_base = ToolRegistry::load(&app).skills_dir(&target)?;
 
     if !target_base.exists() {
         fs::create_dir_all(&target_base).map_err(|e| e.to_string())?;
@@ -29,10 +29,18 @@ pub fn get_version(program: &str, args: &[&str]) -> Option<String> {
     #[cfg(target_os = "windows")]
     let program = if program == "npm" { "npm.cmd" } else { program };
 
-    create_background_command(program)
-        .args(args)
-        .output()
+    let mut cmd = create_background_command(program);
+    cmd.args(args);
+    get_command_version(cmd)
+}
+
+/// `get_version` for a prepared command, e.g. one with a Node installation's PATH.
+/// Stdin is closed and the process killed after `PROBE_TIMEOUT`, so a tool that
+/// prompts (first-run setup, login) can't hang the status check.
+pub fn get_command_version(mut cmd: Command) -> Option<String> {
+    output_with_timeout(&mut cmd, PROBE_TIMEOUT)
         .ok()
+        .flatten()
         .and_then(|output| {
             if output.status.success() || !output.stderr.is_empty() {
                 // Combine stdout and stderr because some tools (like java) output version to stderr
@@ -52,6 +60,255 @@ pub fn get_version(program: &str, args: &[&str]) -> Option<String> {
         })
 }
 
+/// Writes a file so readers see either the old or the new content, never a
+/// truncated mix: a temp file in the same directory is written, fsynced and
+/// renamed over the target. The target's permissions carry over, and a symlinked
+/// target (e.g. dotfiles managed with stow) is written through rather than replaced.
+pub fn atomic_write(path: &std::path::Path, content: &[u8]) -> Result<(), String> {
+    let target = if path.is_symlink() {
+        fs::canonicalize(path).map_err(|e| e.to_string())?
+    } else {
+        path.to_path_buf()
+    };
+    let dir = target
+        .parent()
+        .ok_or_else(|| format!("Invalid path: {}", target.to_string_lossy()))?;
+    if !dir.as_os_str().is_empty() && !dir.exists() {
+        fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+
+    let file_name = target
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default();
+    let tmp = target.with_file_name(format!(".{}.tmp-{}-{}", file_name, std::process::id(), nanos));
+
+    let result = (|| {
+        let existing = fs::metadata(&target).ok().map(|meta| meta.permissions());
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        // Create the temp file with the target's mode so a private file (API keys)
+        // is never readable by others, not even before the permissions are copied
+        #[cfg(unix)]
+        if let Some(perms) = &existing {
+            use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+            options.mode(perms.mode() & 0o777);
+        }
+        let mut file = options.open(&tmp)?;
+        // The mode given at open is filtered by the umask; restore it exactly
+        if let Some(perms) = existing {
+            fs::set_permissions(&tmp, perms)?;
+        }
+        file.write_all(content)?;
+        file.sync_all()?;
+        drop(file);
+        fs::rename(&tmp, &target)?;
+
+        // Persist the rename itself; directories can't be opened this way on Windows
+        #[cfg(unix)]
+        fs::File::open(dir)?.sync_all()?;
+        Ok::<(), std::io::Error>(())
+    })();
+
+    result.map_err(|e| {
+        let _ = fs::remove_file(&tmp);
+        format!("Failed to write {}: {}", target.to_string_lossy(), e)
+    })
+}
+
+/// Copies `path` into `backup_dir` as `<name>.1`, shifting older copies up and
+/// keeping at most `keep` of them. Does nothing if `path` doesn't exist yet.
+pub fn rotate_backups(path: &std::path::Path, backup_dir: &std::path::Path, name: &str, keep: usize) -> Result<(), String> {
+    if keep == 0 || !path.exists() {
+        return Ok(());
+    }
+    fs::create_dir_all(backup_dir).map_err(|e| e.to_string())?;
+
+    let backup = |n: usize| backup_dir.join(format!("{}.{}", name, n));
+    let _ = fs::remove_file(backup(keep));
+    for n in (1..keep).rev() {
+        if backup(n).exists() {
+            fs::rename(backup(n), backup(n + 1)).map_err(|e| e.to_string())?;
+        }
+    }
+    fs::copy(path, backup(1)).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Default limit for a single version probe. Some shims wait on stdin or kick
+/// off a first-run login instead of printing a version.
+pub const PROBE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ProbeStatus {
+    Found,
+    #[default]
+    NotFound,
+    TimedOut,
+    Error,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ProbeResult {
+    pub status: ProbeStatus,
+    pub version: Option<String>,
+    pub error: Option<String>,
+}
+
+impl ProbeResult {
+    pub fn found(version: String) -> Self {
+        ProbeResult {
+            status: ProbeStatus::Found,
+            version: Some(version),
+            error: None,
+        }
+    }
+
+    fn failed(status: ProbeStatus, error: String) -> Self {
+        ProbeResult {
+            status,
+            version: None,
+            error: Some(error),
+        }
+    }
+}
+
+/// Runs a command to completion with stdin closed, killing it after `timeout`.
+/// Returns None if it had to be killed. The command runs in its own process
+/// group on Unix so a timeout also takes down anything it started.
+pub fn output_with_timeout(
+    cmd: &mut Command,
+    timeout: std::time::Duration,
+) -> std::io::Result<Option<std::process::Output>> {
+    cmd.stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped());
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        cmd.process_group(0);
+    }
+    let mut child = cmd.spawn()?;
+
+    // Drain the pipes on their own threads so a chatty process can't fill them and
+    // stall. They collect into shared buffers, so if a background process started
+    // by the command keeps a pipe open we can stop waiting and keep what was read.
+    let (done_tx, done_rx) = std::sync::mpsc::channel::<()>();
+    let drain = |pipe: Option<Box<dyn Read + Send>>| {
+        let buf = Arc::new(Mutex::new(Vec::new()));
+        let (thread_buf, done_tx) = (buf.clone(), done_tx.clone());
+        thread::spawn(move || {
+            if let Some(mut pipe) = pipe {
+                let mut chunk = [0u8; 8192];
+                while let Ok(n @ 1..) = pipe.read(&mut chunk) {
+                    thread_buf.lock().unwrap().extend_from_slice(&chunk[..n]);
+                }
+            }
+            let _ = done_tx.send(());
+        });
+        buf
+    };
+    let stdout = drain(child.stdout.take().map(|p| Box::new(p) as Box<dyn Read + Send>));
+    let stderr = drain(child.stderr.take().map(|p| Box::new(p) as Box<dyn Read + Send>));
+    drop(done_tx);
+
+    let deadline = std::time::Instant::now() + timeout;
+    let status = loop {
+        match child.try_wait()? {
+            Some(status) => break status,
+            None if std::time::Instant::now() >= deadline => {
+                kill_process_group(&mut child);
+                let _ = child.wait();
+                return Ok(None);
+            }
+            None => thread::sleep(std::time::Duration::from_millis(20)),
+        }
+    };
+
+    for _ in 0..2 {
+        let remaining = deadline.saturating_duration_since(std::time::Instant::now());
+        if done_rx.recv_timeout(remaining).is_err() {
+            println!("Output of {:?} still open after exit; not waiting for it", cmd.get_program());
+            break;
+        }
+    }
+
+    let take = |buf: Arc<Mutex<Vec<u8>>>| std::mem::take(&mut *buf.lock().unwrap());
+    Ok(Some(std::process::Output {
+        status,
+        stdout: take(stdout),
+        stderr: take(stderr),
+    }))
+}
+
+/// Kills a child started by `output_with_timeout` together with its process group.
+fn kill_process_group(child: &mut std::process::Child) {
+    #[cfg(unix)]
+    unsafe {
+        libc::kill(-(child.id() as libc::pid_t), libc::SIGKILL);
+    }
+    let _ = child.kill();
+}
+
+/// Like `get_version`, but with stdin closed and the process killed after `timeout`.
+pub fn probe_version(program: &str, args: &[&str], timeout: std::time::Duration) -> ProbeResult {
+    #[cfg(target_os = "windows")]
+    let program = if program == "npm" { "npm.cmd" } else { program };
+
+    let output = match output_with_timeout(create_background_command(program).args(args), timeout) {
+        Ok(Some(output)) => output,
+        Ok(None) => {
+            return ProbeResult::failed(
+                ProbeStatus::TimedOut,
+                format!("{} did not respond within {}s", program, timeout.as_secs()),
+            )
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return ProbeResult::default(),
+        Err(e) => return ProbeResult::failed(ProbeStatus::Error, e.to_string()),
+    };
+
+    // Same rule as get_version: some tools (like java) print the version to stderr
+    if !output.status.success() && output.stderr.is_empty() {
+        return ProbeResult::failed(ProbeStatus::Error, format!("{} exited with {}", program, output.status));
+    }
+    let combined = format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    match combined.lines().map(|l| l.trim()).find(|l| !l.is_empty()) {
+        Some(line) => ProbeResult::found(line.to_string()),
+        None => ProbeResult::failed(ProbeStatus::Error, format!("{} printed no version", program)),
+    }
+}
+
+/// Probes each candidate in turn until one is present on the system.
+pub fn probe_candidates(programs: &[&str], args: &[&str], timeout: std::time::Duration) -> ProbeResult {
+    let mut result = ProbeResult::default();
+    for program in programs {
+        result = probe_version(program, args, timeout);
+        if result.status != ProbeStatus::NotFound {
+            break;
+        }
+    }
+    result
+}
+
+/// Pulls a semver-looking version out of `--version` output such as
+/// "1.0.3 (Claude Code)" or "codex-cli 0.9.2".
+pub fn extract_version(output: &str) -> Option<String> {
+    static VERSION_RE: std::sync::OnceLock<regex::Regex> = std::sync::OnceLock::new();
+    let re = VERSION_RE.get_or_init(|| {
+        regex::Regex::new(r"\d+\.\d+\.\d+(?:-[0-9A-Za-z.-]+)?").expect("version pattern is valid")
+    });
+    re.find(output).map(|m| m.as_str().to_string())
+}
+
 pub fn get_proxy_client(app: &AppHandle) -> Result<reqwest::Client, String> {
     let config = get_app_config(app.clone());
     let mut builder = reqwest::Client::builder();
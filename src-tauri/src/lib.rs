@@ -2,6 +2,7 @@
 pub mod commands;
 use commands::pty::*;
 use commands::config::*;
+use commands::env::{ToolJobs, ToolUpdateCache};
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
@@ -10,33 +11,72 @@ pub fn run() {
         .plugin(tauri_plugin_log::Builder::default().build())
         .setup(|app| {
             let app_handle = app.handle().clone();
-            
+
+            // Older versions kept config next to the executable
+            migrate_exe_relative_config(&app_handle);
+
             // Ensure config exists on startup
             let _ = get_app_config(app_handle.clone());
-            
+
+            // Restore project directories granted at runtime via `allow_directory`
+            commands::fs::apply_allowed_directories(&app_handle);
+
+            // Pick up PATH from the user's login shell for background commands
+            commands::shell_env::init_shell_env(&app_handle);
+
+            // Older versions saved API keys in plaintext in config.json
+            commands::secrets::migrate_plaintext_secrets(&app_handle);
+
             Ok(())
         })
         .manage(AppPty::default())
+        .manage(ToolJobs::default())
+        .manage(ToolUpdateCache::default())
         .invoke_handler(tauri::generate_handler![
             commands::pty::pty_open,
             commands::pty::pty_close,
             commands::pty::pty_write,
             commands::pty::pty_resize,
             commands::pty::pty_exists,
+            commands::pty::pty_status,
+            commands::pty::pty_attach,
+            commands::pty::get_terminal_profiles,
+            commands::pty::save_terminal_profiles,
+            commands::recording::list_recordings,
+            commands::recording::replay_recording,
+            commands::recording::delete_recording,
             commands::env::check_executable,
             commands::env::check_environment,
             commands::env::launch_tool,
             commands::env::launch_tool_with_args,
+            commands::env::launch_tool_in_pty,
             commands::env::install_tool,
             commands::env::update_tool,
             commands::env::uninstall_tool,
+            commands::env::cancel_tool_job,
+            commands::env::rollback_tool,
+            commands::env::list_tool_versions,
             commands::env::check_tool_status,
+            commands::env::check_tool_updates,
+            commands::probes::list_environment_probes,
+            commands::probes::save_environment_probes,
+            commands::doctor::run_environment_doctor,
+            commands::node::list_node_installs,
+            commands::node::select_node_install,
+            commands::registry::list_tools,
+            commands::secrets::set_secret,
+            commands::secrets::delete_secret,
+            commands::secrets::has_secret,
             commands::config::get_app_config,
             commands::config::save_app_config,
             commands::fs::open_url,
             commands::fs::download_file,
             commands::fs::extract_file,
             commands::chat::fetch_remote_models,
+            commands::chat::chat_completion,
+            commands::ollama::ollama_list_models,
+            commands::ollama::ollama_pull_model,
+            commands::ollama::ollama_delete_model,
             commands::fs::search_files,
             commands::fs::get_git_diff,
             commands::fs::get_changed_files,
@@ -45,8 +85,12 @@ pub fn run() {
             commands::skills::install_skills,
             commands::config::get_config_file,
             commands::config::save_config_file,
+            commands::config_file::validate_config_file,
+            commands::config_file::get_config_value,
+            commands::config_file::set_config_value,
             commands::skills::uninstall_skills,
             commands::fs::open_folder,
+            commands::fs::allow_directory,
             commands::skills::inspect_mcp_server,
             commands::chat::get_models,
             commands::chat::get_chat_sessions,
@@ -9,6 +9,7 @@ import React, { useState, useEffect } from 'react';
 import { Card, Button, Input, Form, message, Space, Alert, Tabs, Switch, InputNumber, Select, Divider, AutoComplete, Row, Col } from 'antd';
 import { SaveOutlined, KeyOutlined, ReloadOutlined, SettingOutlined, SafetyOutlined, ExperimentOutlined, BugOutlined, CloudDownloadOutlined } from '@ant-design/icons';
 import { invoke } from '@tauri-apps/api/core';
+import { formatConfigFileError } from '../lib/tauri';
 import { useTranslation } from 'react-i18next';
 const SETTINGS_PATH = '~/.claude.json';
 
@@ -74,7 +75,7 @@ const ClaudeCodeAuthEditor: React.FC = () => {
             // Reload to sync the whole page if needed
             loadConfig();
         } catch (error) {
-            message.error(t('aiSettings.mcpConfig.saveFailed', 'Failed to save config') + ': ' + error);
+            message.error(t('aiSettings.mcpConfig.saveFailed', 'Failed to save config') + ': ' + formatConfigFileError(error));
         }
     };
 
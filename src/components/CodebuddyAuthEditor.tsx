@@ -9,6 +9,7 @@ import React, { useState, useEffect } from 'react';
 import { Card, Button, Input, Form, message, Space, Alert, Tabs, Switch, InputNumber, Select, Divider, Row, Col } from 'antd';
 import { SaveOutlined, KeyOutlined, ReloadOutlined, SettingOutlined, PlusOutlined, DeleteOutlined, CloudDownloadOutlined, BuildOutlined, ControlOutlined } from '@ant-design/icons';
 import { invoke } from '@tauri-apps/api/core';
+import { formatConfigFileError } from '../lib/tauri';
 import { useTranslation } from 'react-i18next';
 
 const SETTINGS_PATH = '~/.codebuddy/settings.json';
@@ -80,7 +81,7 @@ const CodebuddyAuthEditor: React.FC = () => {
             await invoke('save_config_file', { path: SETTINGS_PATH, content: JSON.stringify(finalConfig, null, 2) });
             message.success(t('aiSettings.mcpConfig.saved', 'Saved CodeBuddy settings'));
         } catch (error) {
-            message.error(t('aiSettings.mcpConfig.saveFailed', 'Failed to save config') + ': ' + error);
+            message.error(t('aiSettings.mcpConfig.saveFailed', 'Failed to save config') + ': ' + formatConfigFileError(error));
         }
     };
 
@@ -9,6 +9,7 @@ import React, { useState, useEffect } from 'react';
 import { Card, Button, Input, Form, message, Space, Alert, Tabs, Select, Divider, Row, Col, InputNumber } from 'antd';
 import { SaveOutlined, KeyOutlined, EyeInvisibleOutlined, EyeOutlined, ReloadOutlined, SettingOutlined, RocketOutlined } from '@ant-design/icons';
 import { invoke } from '@tauri-apps/api/core';
+import { formatConfigFileError } from '../lib/tauri';
 import { useTranslation } from 'react-i18next';
 import * as TOML from 'smol-toml';
 
@@ -116,7 +117,7 @@ const CodexAuthEditor: React.FC = () => {
 
             message.success(t('aiSettings.mcpConfig.saved', 'Settings saved successfully'));
         } catch (error) {
-            message.error(t('aiSettings.mcpConfig.saveFailed', 'Failed to save settings:') + ' ' + error);
+            message.error(t('aiSettings.mcpConfig.saveFailed', 'Failed to save settings:') + ' ' + formatConfigFileError(error));
         } finally {
             setLoading(false);
         }
@@ -9,6 +9,7 @@ import React, { useState, useEffect } from 'react';
 import { Card, Button, Input, Form, message, Space, Alert, Modal, Switch, Divider } from 'antd';
 import { SaveOutlined, GithubOutlined, ReloadOutlined, SettingOutlined, BugOutlined } from '@ant-design/icons';
 import { invoke } from '@tauri-apps/api/core';
+import { formatConfigFileError } from '../lib/tauri';
 import { useTranslation } from 'react-i18next';
 import { useAppStore } from '../store/appStore';
 import { useNavigate } from 'react-router-dom';
@@ -70,7 +71,7 @@ const CopilotAuthEditor: React.FC = () => {
             await invoke('save_config_file', { path: CONFIG_PATH, content: JSON.stringify(values, null, 2) });
             message.success(t('aiSettings.mcpConfig.saved', 'Saved Copilot settings'));
         } catch (error) {
-            message.error(t('aiSettings.mcpConfig.saveFailed', 'Failed to save config') + ': ' + error);
+            message.error(t('aiSettings.mcpConfig.saveFailed', 'Failed to save config') + ': ' + formatConfigFileError(error));
         }
     };
 
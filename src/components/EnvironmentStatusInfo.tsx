@@ -26,9 +26,9 @@ const EnvironmentStatusInfo = () => {
     }
 
     const items = [
-        { label: 'Node', version: envStatus.node_version },
-        { label: 'npm', version: envStatus.npm_version },
-        { label: 'Git', version: envStatus.git_version },
+        { label: 'Node', version: envStatus.node_version?.version },
+        { label: 'npm', version: envStatus.npm_version?.version },
+        { label: 'Git', version: envStatus.git_version?.version },
     ];
 
     return (
@@ -9,6 +9,7 @@ import React, { useState, useEffect } from 'react';
 import { Card, Button, Input, Form, message, Space, Alert, Tabs, Switch, InputNumber, Select, Divider, AutoComplete, Row, Col, Modal } from 'antd';
 import { SaveOutlined, KeyOutlined, ReloadOutlined, SettingOutlined, SafetyOutlined, ExperimentOutlined, BugOutlined, CloudDownloadOutlined, GoogleOutlined, UserOutlined, AppstoreOutlined, FilterOutlined, HistoryOutlined } from '@ant-design/icons';
 import { invoke } from '@tauri-apps/api/core';
+import { formatConfigFileError } from '../lib/tauri';
 import { useNavigate } from 'react-router-dom';
 import { useTranslation } from 'react-i18next';
 import { useAppStore } from '../store/appStore';
@@ -92,7 +93,7 @@ const GeminiAuthEditor: React.FC = () => {
             message.success(t('aiSettings.mcpConfig.saved', 'Saved Gemini settings'));
             loadConfig();
         } catch (error) {
-            message.error(t('aiSettings.mcpConfig.saveFailed', 'Failed to save config') + ': ' + error);
+            message.error(t('aiSettings.mcpConfig.saveFailed', 'Failed to save config') + ': ' + formatConfigFileError(error));
         }
     };
 
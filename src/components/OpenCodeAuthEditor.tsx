@@ -9,6 +9,7 @@ import React, { useState, useEffect } from 'react';
 import { Card, Button, Input, Form, AutoComplete, message, Space, Alert, Tabs, Switch, InputNumber, Select, Row, Col, Divider } from 'antd';
 import { SaveOutlined, KeyOutlined, EyeInvisibleOutlined, EyeOutlined, ReloadOutlined, SettingOutlined, CloudServerOutlined, SafetyOutlined, ExperimentOutlined, AppstoreOutlined, GlobalOutlined, CloudDownloadOutlined } from '@ant-design/icons';
 import { invoke } from '@tauri-apps/api/core';
+import { formatConfigFileError } from '../lib/tauri';
 import { useTranslation } from 'react-i18next';
 
 const AUTH_CONFIG_PATH = '~/.local/share/opencode/auth.json';
@@ -257,7 +258,7 @@ const OpenCodeAuthEditor: React.FC = () => {
 
             message.success(t('aiSettings.mcpConfig.saved', 'Settings saved successfully'));
         } catch (error) {
-            message.error(t('aiSettings.mcpConfig.saveFailed', 'Failed to save settings:') + ' ' + error);
+            message.error(t('aiSettings.mcpConfig.saveFailed', 'Failed to save settings:') + ' ' + formatConfigFileError(error));
         } finally {
             setLoading(false);
         }
@@ -12,6 +12,7 @@ import {
 } from '@ant-design/icons';
 import { useTranslation } from 'react-i18next';
 import { invoke } from '@tauri-apps/api/core';
+import { formatConfigFileError } from '../lib/tauri';
 
 const { Text, Paragraph, Title } = Typography;
 
@@ -232,7 +233,7 @@ const PluginManagement: React.FC = () => {
             setInstalledKeys((prev: string[]) => [...prev, plugin.key]);
         } catch (error) {
             console.error(error);
-            message.error(t('app.messages.installFailed', { name: pluginName, error: String(error) }));
+            message.error(t('app.messages.installFailed', { name: pluginName, error: formatConfigFileError(error) }));
         } finally {
             setLoading((prev: Record<string, boolean>) => ({ ...prev, [plugin.key]: false }));
         }
@@ -270,7 +271,7 @@ const PluginManagement: React.FC = () => {
             setInstalledKeys((prev: string[]) => prev.filter(k => k !== plugin.key));
         } catch (error) {
             console.error(error);
-            message.error(t('app.messages.uninstallFailed', { name: pluginName, error: String(error) }));
+            message.error(t('app.messages.uninstallFailed', { name: pluginName, error: formatConfigFileError(error) }));
         } finally {
             setLoading((prev: Record<string, boolean>) => ({ ...prev, [plugin.key]: false }));
         }
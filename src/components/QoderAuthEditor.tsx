@@ -9,6 +9,7 @@ import React, { useState, useEffect } from 'react';
 import { Card, Button, Input, Form, message, Space, Alert, Tabs, Switch, InputNumber, Select, Divider, Row, Col, Tooltip } from 'antd';
 import { SaveOutlined, KeyOutlined, ReloadOutlined, SettingOutlined, DesktopOutlined, SafetyOutlined, ExperimentOutlined, GlobalOutlined, PlusOutlined, DeleteOutlined, CloudDownloadOutlined } from '@ant-design/icons';
 import { invoke } from '@tauri-apps/api/core';
+import { formatConfigFileError } from '../lib/tauri';
 import { useTranslation } from 'react-i18next';
 
 const SETTINGS_PATH = '~/.qoder/settings.json';
@@ -76,7 +77,7 @@ const QoderAuthEditor: React.FC = () => {
             await invoke('save_config_file', { path: SETTINGS_PATH, content: JSON.stringify(finalConfig, null, 2) });
             message.success(t('aiSettings.mcpConfig.saved', 'Saved Qoder settings'));
         } catch (error) {
-            message.error(t('aiSettings.mcpConfig.saveFailed', 'Failed to save config') + ': ' + error);
+            message.error(t('aiSettings.mcpConfig.saveFailed', 'Failed to save config') + ': ' + formatConfigFileError(error));
         }
     };
 
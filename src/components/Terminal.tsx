@@ -18,7 +18,7 @@ import {
     QuestionCircleOutlined
 } from '@ant-design/icons';
 import '@xterm/xterm/css/xterm.css';
-import { ptyOpen, ptyWrite, ptyResize, ptyClose, ptyExists } from '../lib/tauri';
+import { ptyOpen, ptyWrite, ptyResize, ptyClose, ptyExists, ptyAttach } from '../lib/tauri';
 import { invoke } from '@tauri-apps/api/core';
 import { useAppStore } from '../store/appStore';
 import { useTranslation } from 'react-i18next';
@@ -84,9 +84,15 @@ const TerminalUI = ({ sessionId, initialCommand }: TerminalProps) => {
 
         const initPty = async () => {
             // 1. Register data listener first so we don't miss the initial prompt
-            const unlistenFn = await listen<{ id: string, data: number[] }>('pty-data', (event) => {
+            // While reattaching, events are held back until the scrollback snapshot is written
+            let pending: { data: number[], offset: number }[] | null = null;
+            const unlistenFn = await listen<{ id: string, data: number[], offset: number }>('pty-data', (event) => {
                 if (event.payload.id === sessionId) {
-                    term.write(new Uint8Array(event.payload.data));
+                    if (pending) {
+                        pending.push(event.payload);
+                    } else {
+                        term.write(new Uint8Array(event.payload.data));
+                    }
                 }
             });
             unlisten = unlistenFn;
@@ -116,7 +122,17 @@ const TerminalUI = ({ sessionId, initialCommand }: TerminalProps) => {
                     exists = await ptyExists(sessionId);
 
                     if (exists) {
-                        // If PTY exists, just resize and attach.
+                        // If PTY exists, replay its scrollback, then resize and attach.
+                        pending = [];
+                        const attached = await ptyAttach(sessionId);
+                        if (attached) {
+                            term.write(new Uint8Array(attached.data));
+                        }
+                        const startOffset = attached?.offset ?? 0;
+                        pending.filter(chunk => chunk.offset >= startOffset)
+                            .forEach(chunk => term.write(new Uint8Array(chunk.data)));
+                        pending = null;
+
                         await ptyResize(sessionId, initialCols, initialRows);
                         setIsReady(true);
                     } else {
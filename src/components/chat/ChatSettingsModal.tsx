@@ -1,7 +1,15 @@
 import React, { useState } from 'react';
 import { Modal, Form, Input, Button, Row, Col, Select, message } from 'antd';
 import { useTranslation } from 'react-i18next';
-import { fetchRemoteModels } from '../../lib/tauri';
+import { fetchRemoteModels, ollamaListModels, setSecret, toolApiKeyId, type ChatApiType } from '../../lib/tauri';
+import { useAppStore } from '../../store/appStore';
+
+/** Endpoint the backend uses when Base URL is left empty */
+const DEFAULT_BASE_URLS: Record<string, string> = {
+    openai: 'https://api.openai.com/v1',
+    anthropic: 'https://api.anthropic.com/v1',
+    google: 'https://generativelanguage.googleapis.com/v1beta',
+};
 
 interface ChatSettingsModalProps {
     open: boolean;
@@ -27,16 +35,36 @@ const ChatSettingsModal: React.FC<ChatSettingsModalProps> = ({
     const [modelSelectOpen, setModelSelectOpen] = useState(false);
     const [fetchedModels, setFetchedModels] = useState<string[]>([]);
     const [fetchingModels, setFetchingModels] = useState(false);
+    const apiType: ChatApiType = Form.useWatch('apiType', form) || 'openai';
+    // Ollama runs locally and needs no key; an empty Base URL uses the local AI setting
+    const isOllama = apiType === 'ollama';
+    const localAiBaseUrl = useAppStore((state) => state.localAiBaseUrl);
+
+    const storedKeyId = activeChatToolId ? toolConfigs[activeChatToolId]?.llmApiKeyId : undefined;
 
-    const onFinish = (values: any) => {
+    const onFinish = async (values: any) => {
         const newProvider = values.provider.trim();
         if (newProvider) {
+            // Keys go straight to the secret store; the config only keeps the id.
+            // A blank field keeps the stored key.
+            let llmApiKeyId = newProvider === activeChatToolId ? storedKeyId : undefined;
+            if (values.apiKey) {
+                llmApiKeyId = toolApiKeyId(newProvider);
+                try {
+                    await setSecret(llmApiKeyId, values.apiKey);
+                } catch (e) {
+                    message.error(t('chat.saveKeyError', 'Failed to store API Key: {{error}}', { error: String(e) }));
+                    return;
+                }
+            }
             addChatProvider(newProvider);
             const modelValue = Array.isArray(values.model) ? values.model[0] : values.model;
             setToolConfig(newProvider, {
-                llmApiKey: values.apiKey,
+                llmApiKey: undefined,
+                llmApiKeyId,
                 llmModel: modelValue,
-                llmBaseUrl: values.baseUrl
+                llmBaseUrl: values.baseUrl,
+                llmApiType: values.apiType
             });
             setActiveChatToolId(newProvider);
             message.success(t('chat.saved', 'Settings saved!'));
@@ -58,7 +86,8 @@ const ChatSettingsModal: React.FC<ChatSettingsModalProps> = ({
                 layout="vertical"
                 initialValues={{
                     provider: activeChatToolId || '',
-                    apiKey: activeChatToolId ? toolConfigs[activeChatToolId]?.llmApiKey || '' : '',
+                    apiType: (activeChatToolId && toolConfigs[activeChatToolId]?.llmApiType) || 'openai',
+                    apiKey: '',
                     model: activeChatToolId && toolConfigs[activeChatToolId]?.llmModel ? [toolConfigs[activeChatToolId]?.llmModel] : [],
                     baseUrl: activeChatToolId ? toolConfigs[activeChatToolId]?.llmBaseUrl || '' : ''
                 }}
@@ -71,19 +100,32 @@ const ChatSettingsModal: React.FC<ChatSettingsModalProps> = ({
                 >
                     <Input placeholder="e.g. google, openai, deepseek..." />
                 </Form.Item>
+                <Form.Item
+                    label={t('chat.apiType', 'API Type')}
+                    name="apiType"
+                >
+                    <Select
+                        options={[
+                            { value: 'openai', label: t('chat.apiTypeOpenAi', 'OpenAI-compatible') },
+                            { value: 'anthropic', label: 'Anthropic' },
+                            { value: 'google', label: 'Google Gemini' },
+                            { value: 'ollama', label: t('chat.apiTypeOllama', 'Ollama (local)') },
+                        ]}
+                    />
+                </Form.Item>
                 <Form.Item
                     label={t('chat.apiKey', 'API Key')}
                     name="apiKey"
-                    rules={[{ required: true, message: t('chat.enterApiKey', 'Please input an API Key') }]}
+                    rules={[{ required: !storedKeyId && !isOllama, message: t('chat.enterApiKey', 'Please input an API Key') }]}
                 >
-                    <Input.Password placeholder="sk-..." />
+                    <Input.Password placeholder={storedKeyId ? t('chat.apiKeyStored', 'Stored securely — leave blank to keep') : 'sk-...'} />
                 </Form.Item>
                 <Form.Item
                     label={t('chat.baseUrl', 'Base URL')}
                     name="baseUrl"
-                    rules={[{ required: true, message: t('chat.enterBaseUrl', 'Please input the Base URL') }]}
+                    tooltip={t('chat.baseUrlTooltip', 'Leave empty to use the provider\'s default endpoint')}
                 >
-                    <Input placeholder="https://api.openai.com/v1" />
+                    <Input placeholder={isOllama ? localAiBaseUrl : DEFAULT_BASE_URLS[apiType]} />
                 </Form.Item>
                 <Form.Item
                     label={t('chat.model', 'Model Name')}
@@ -118,13 +160,15 @@ const ChatSettingsModal: React.FC<ChatSettingsModalProps> = ({
                                 loading={fetchingModels}
                                 onClick={async () => {
                                     const values = form.getFieldsValue();
-                                    if (!values.baseUrl || !values.apiKey) {
-                                        message.warning(t('chat.fetchErrorNoConfig', 'Please fill in Base URL and API Key first'));
+                                    if (!values.apiKey && !storedKeyId && !isOllama) {
+                                        message.warning(t('chat.fetchErrorNoKey', 'Please fill in the API Key first'));
                                         return;
                                     }
                                     setFetchingModels(true);
                                     try {
-                                        const models = await fetchRemoteModels(values.baseUrl, values.apiKey);
+                                        const models = isOllama
+                                            ? (await ollamaListModels()).map((m) => m.name)
+                                            : await fetchRemoteModels(values.baseUrl || '', values.apiKey || '', storedKeyId, values.apiType);
                                         setFetchedModels(models);
                                         setModelSelectOpen(true);
                                         message.success(t('chat.fetchSuccess', 'Fetched {{count}} models', { count: models.length }));
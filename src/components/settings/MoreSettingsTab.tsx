@@ -22,6 +22,7 @@ import { useState, useEffect } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { save, open } from '@tauri-apps/plugin-dialog';
 import { readTextFile, writeTextFile } from '@tauri-apps/plugin-fs';
+import { runEnvironmentDoctor, formatConfigFileError } from '../../lib/tauri';
 
 const { Paragraph, Text } = Typography;
 
@@ -65,20 +66,37 @@ const MoreSettingsTab = ({ t, tools }: MoreSettingsTabProps) => {
     const runDiagnostics = async () => {
         setDiagLoading(true);
         try {
-            const status = await invoke<any>('check_environment');
+            const [status, doctor] = await Promise.all([
+                invoke<any>('check_environment'),
+                runEnvironmentDoctor(),
+            ]);
             setDiagnostics(status);
 
+            const toolLines = doctor.tools
+                .filter(tool => tool.installs.length > 0)
+                .map(tool => `- ${tool.program}:\n${tool.installs.map(i => `    ${i.path} (${i.version || 'unknown'})`).join('\n')}`);
+            const issueLines = doctor.issues.map(issue =>
+                `[${issue.severity}] ${issue.message}${issue.fix ? `\n    Fix: ${issue.fix}` : ''}`);
+
             const report = `AI System Diagnostic Report (${new Date().toLocaleString()})
 ----------------------------------------
-Node.js: ${status.node_version || 'Not Found'}
-npm: ${status.npm_version || 'Not Found'}
-Git: ${status.git_version || 'Not Found'}
-Python: ${status.python_version || 'Not Found'}
-Go: ${status.go_version || 'Not Found'}
-Java: ${status.java_version || 'Not Found'}
+Node.js: ${status.node_version?.version || 'Not Found'}
+npm: ${status.npm_version?.version || 'Not Found'}
+Git: ${status.git_version?.version || 'Not Found'}
+Python: ${status.python_version?.version || 'Not Found'}
+Go: ${status.go_version?.version || 'Not Found'}
+Java: ${status.java_version?.version || 'Not Found'}
 ----------------------------------------
 Installed Tools:
 ${tools.map(tool => `- ${tool.displayName}`).join('\n')}
+----------------------------------------
+npm global prefix: ${doctor.npm.prefix || 'Not Found'}${doctor.npm.bin_dir_on_path ? '' : ' (not on PATH)'}
+Network (${doctor.proxy.proxy || 'direct'}): ${doctor.proxy.reachable ? `OK, ${doctor.proxy.latency_ms}ms` : `Unreachable - ${doctor.proxy.error}`}
+Tool Locations:
+${toolLines.join('\n') || '- none'}
+----------------------------------------
+Issues:
+${issueLines.join('\n') || 'No problems found'}
 `;
 
             Modal.info({
@@ -196,7 +214,7 @@ ${tools.map(tool => `- ${tool.displayName}`).join('\n')}
             setTimeout(() => window.location.reload(), 1500);
         } catch (error) {
             console.error('Import failed:', error);
-            message.error('Import failed: ' + error);
+            message.error('Import failed: ' + formatConfigFileError(error));
         }
     };
 
@@ -6,19 +6,39 @@
  */
 
 import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+
+export type ProbeStatus = 'found' | 'not_found' | 'timed_out' | 'error';
+
+export interface ProbeResult {
+    status: ProbeStatus;
+    version: string | null;
+    error: string | null;
+}
 
 export interface EnvironmentStatus {
-    node_version: string | null;
-    npm_version: string | null;
-    git_version: string | null;
-    python_version: string | null;
-    go_version: string | null;
-    java_version: string | null;
-    gh_version: string | null;
-    claude_version: string | null;
-    opencode_version: string | null;
-    qoder_version: string | null;
-    codebuddy_version: string | null;
+    node_version: ProbeResult;
+    npm_version: ProbeResult;
+    git_version: ProbeResult;
+    python_version: ProbeResult;
+    go_version: ProbeResult;
+    java_version: ProbeResult;
+    gh_version: ProbeResult;
+    claude_version: ProbeResult;
+    opencode_version: ProbeResult;
+    qoder_version: ProbeResult;
+    codebuddy_version: ProbeResult;
+    /** Every probe by name, including ones added through `environment_probes` in the config */
+    probes: Record<string, ProbeResult>;
+}
+
+export interface ProbeDefinition {
+    name: string;
+    label: string | null;
+    programs: string[];
+    args: string[];
+    version_regex: string | null;
+    disabled: boolean;
 }
 
 export interface ChatMessage {
@@ -50,22 +70,79 @@ export const checkEnvironment = async (): Promise<EnvironmentStatus> => {
     } catch (e) {
         console.warn("Tauri invoke failed, falling back to mock data (Browser Mode)", e);
         // Return mock data for browser preview
+        const mock = (version: string): ProbeResult => ({ status: 'found', version, error: null });
         return {
-            node_version: "v20.11.0 (Mock)",
-            npm_version: "10.2.4 (Mock)",
-            git_version: "2.43.0.windows.1 (Mock)",
-            python_version: "3.12.1 (Mock)",
-            go_version: "go1.22.0 windows/amd64 (Mock)",
-            java_version: "openjdk version \"21.0.2\" 2024-01-16 (Mock)",
-            gh_version: "2.40.0 (Mock)",
-            claude_version: "0.2.0 (Mock)",
-            opencode_version: "1.0.0 (Mock)",
-            qoder_version: "0.5.0 (Mock)",
-            codebuddy_version: "0.1.0 (Mock)",
+            node_version: mock("v20.11.0 (Mock)"),
+            npm_version: mock("10.2.4 (Mock)"),
+            git_version: mock("2.43.0.windows.1 (Mock)"),
+            python_version: mock("3.12.1 (Mock)"),
+            go_version: mock("go1.22.0 windows/amd64 (Mock)"),
+            java_version: mock("openjdk version \"21.0.2\" 2024-01-16 (Mock)"),
+            gh_version: mock("2.40.0 (Mock)"),
+            claude_version: mock("0.2.0 (Mock)"),
+            opencode_version: mock("1.0.0 (Mock)"),
+            qoder_version: mock("0.5.0 (Mock)"),
+            codebuddy_version: mock("0.1.0 (Mock)"),
+            probes: {},
         };
     }
 };
 
+export const listEnvironmentProbes = async (): Promise<ProbeDefinition[]> => {
+    try {
+        return await invoke('list_environment_probes');
+    } catch (e) {
+        console.warn("List probes failed (Browser Mode)", e);
+        return [];
+    }
+};
+
+/** Stores probes that add to or override the built-in ones by name; `disabled` switches a built-in off */
+export const saveEnvironmentProbes = async (probes: ProbeDefinition[]): Promise<void> => {
+    await invoke('save_environment_probes', { probes });
+};
+
+export interface DoctorInstall {
+    path: string;
+    version: string | null;
+}
+
+export interface DoctorTool {
+    id: string;
+    program: string;
+    npm_package: string | null;
+    /** In PATH order; the first one is what runs */
+    installs: DoctorInstall[];
+    path_entries: string[];
+}
+
+export interface DoctorIssue {
+    severity: 'error' | 'warning';
+    tool_id: string | null;
+    message: string;
+    fix: string | null;
+}
+
+export interface DoctorReport {
+    path: string[];
+    node: ProbeResult;
+    npm: { prefix: string | null; bin_dir: string | null; bin_dir_on_path: boolean };
+    proxy: {
+        proxy: string | null;
+        url: string;
+        reachable: boolean;
+        status: number | null;
+        latency_ms: number | null;
+        error: string | null;
+    };
+    tools: DoctorTool[];
+    issues: DoctorIssue[];
+}
+
+export const runEnvironmentDoctor = async (): Promise<DoctorReport> => {
+    return await invoke('run_environment_doctor');
+};
+
 export const launchTool = async (toolId: string): Promise<string> => {
     try {
         return await invoke('launch_tool', { toolId });
@@ -75,10 +152,70 @@ export const launchTool = async (toolId: string): Promise<string> => {
     }
 };
 
+/**
+ * Starts a tool inside a new integrated terminal session and returns its id;
+ * open a Terminal with that session id to attach to it.
+ */
+export const launchToolInPty = async (toolId: string, args?: string[], cols?: number, rows?: number): Promise<string> => {
+    return await invoke('launch_tool_in_pty', { toolId, args, cols, rows });
+};
+
 export interface ToolStatus {
     id: string;
     installed: boolean;
     version: string | null;
+    /** Method the backend installed the tool with: npm, pnpm, bun, pipx, uv, brew or archive */
+    install_method?: string | null;
+    /** Version replaced by the last install/update; rollbackTool reinstalls it */
+    previous_version?: string | null;
+    /** Node installation the CLI lives under, e.g. "nvm@20.11.0" or "volta" */
+    node_install?: string | null;
+}
+
+export interface NodeInstall {
+    id: string;
+    manager: 'nvm' | 'fnm' | 'volta' | 'system';
+    version: string;
+    bin_dir: string;
+    root: string;
+    selected: boolean;
+}
+
+export const listNodeInstalls = async (): Promise<NodeInstall[]> => {
+    try {
+        return await invoke('list_node_installs');
+    } catch (e) {
+        console.warn("List Node installs failed (Browser Mode)", e);
+        return [];
+    }
+};
+
+/** Node used for tool installs and launches; null goes back to whatever PATH resolves */
+export const selectNodeInstall = async (id: string | null): Promise<void> => {
+    await invoke('select_node_install', { id });
+};
+
+export interface ToolDefinition {
+    id: string;
+    name: string | null;
+    program: string;
+    aliases: string[];
+    version_args: string[];
+    npm_package: string | null;
+    installers: ({ method: string } & Record<string, unknown>)[];
+    /** Env var names the tool reads its API key, base URL and model from */
+    llm_env: { api_key: string[]; base_url: string[]; model: string[] };
+    skills_dir: string | null;
+}
+
+/** Built-in tools merged with the user's `tools.json` / `tools.toml` next to `config.json`. */
+export const listTools = async (): Promise<ToolDefinition[]> => {
+    try {
+        return await invoke('list_tools');
+    } catch (e) {
+        console.warn("List Tools failed (Browser Mode)", e);
+        return [];
+    }
 }
 
 export const checkToolStatus = async (toolId: string): Promise<ToolStatus> => {
@@ -90,34 +227,91 @@ export const checkToolStatus = async (toolId: string): Promise<ToolStatus> => {
     }
 }
 
-export const installTool = async (toolId: string): Promise<string> => {
+export const installTool = async (toolId: string, jobId?: string, method?: string, version?: string): Promise<string> => {
     try {
-        return await invoke('install_tool', { toolId });
+        return await invoke('install_tool', { toolId, jobId, method, version });
     } catch (e) {
         console.warn("Install failed (Browser Mode)", e);
         return `Mock Installed ${toolId}`;
     }
 };
 
-export const uninstallTool = async (toolId: string): Promise<string> => {
+export const uninstallTool = async (toolId: string, jobId?: string, method?: string): Promise<string> => {
     try {
-        return await invoke('uninstall_tool', { toolId });
+        return await invoke('uninstall_tool', { toolId, jobId, method });
     } catch (e) {
         console.warn("Uninstall failed (Browser Mode)", e);
         return `Mock Uninstalled ${toolId}`;
     }
 };
 
-export const updateTool = async (toolId: string): Promise<string> => {
+export const updateTool = async (toolId: string, jobId?: string, method?: string, version?: string): Promise<string> => {
     try {
-        return await invoke('update_tool', { toolId });
+        return await invoke('update_tool', { toolId, jobId, method, version });
     } catch (e) {
         console.warn("Update failed (Browser Mode)", e);
         return `Mock Updated ${toolId}`;
     }
 };
 
+/**
+ * Install/update/uninstall output streams as `tool-install-log` events ({ jobId, toolId, stream, line })
+ * and finishes with a `tool-install-status` event ({ jobId, toolId, action, status, message }).
+ */
+export const cancelToolJob = async (jobId: string): Promise<void> => {
+    await invoke('cancel_tool_job', { jobId });
+};
+
+export interface ToolUpdateInfo {
+    tool_id: string;
+    current: string | null;
+    latest: string | null;
+    update_available: boolean;
+    error: string | null;
+}
+
+/** Registry lookups are cached for an hour unless `force` is set */
+export const checkToolUpdates = async (toolIds?: string[], force?: boolean): Promise<ToolUpdateInfo[]> => {
+    try {
+        return await invoke('check_tool_updates', { toolIds, force });
+    } catch (e) {
+        console.warn("Update check failed (Browser Mode)", e);
+        return [];
+    }
+};
+
+export const rollbackTool = async (toolId: string, jobId?: string): Promise<string> => {
+    return await invoke('rollback_tool', { toolId, jobId });
+};
+
+export interface ToolVersions {
+    tool_id: string;
+    package: string;
+    dist_tags: Record<string, string>;
+    versions: string[];
+}
+
+export const listToolVersions = async (toolId: string): Promise<ToolVersions> => {
+    try {
+        return await invoke('list_tool_versions', { toolId });
+    } catch (e) {
+        console.warn("List versions failed (Browser Mode)", e);
+        return { tool_id: toolId, package: '', dist_tags: {}, versions: [] };
+    }
+};
+
+export interface TerminalProfile {
+    id: string;
+    name: string;
+    program: string | null;
+    args: string[] | null;
+    cwd: string | null;
+    env: Record<string, string> | null;
+}
+
 export interface AppConfig {
+    /** Set by the backend on every write; configs are migrated to the current version on load */
+    schema_version?: number;
     proxy_type: string | null;
     proxy_address: string | null;
     theme: string | null;
@@ -138,7 +332,6 @@ export interface AppConfig {
     active_tool_id: string | null;
     active_chat_tool_id: string | null;
     chat_providers: string[] | null;
-    env_status: EnvironmentStatus | null;
     tool_statuses: Record<string, ToolStatus> | null;
     tool_configs: Record<string, { working_directory: string | null }> | null;
     global_instructions: string | null;
@@ -148,6 +341,9 @@ export interface AppConfig {
     command_presets: { id: string; name: string; command: string }[] | null;
     chat_sidebar_width: number | null;
     resource_sidebar_width: number | null;
+    // Managed by the backend via `allow_directory`; preserved when omitted on save
+    allowed_directories?: string[] | null;
+    terminal_profiles?: TerminalProfile[] | null;
 }
 
 export const getAppConfig = async (): Promise<AppConfig> => {
@@ -175,7 +371,6 @@ export const getAppConfig = async (): Promise<AppConfig> => {
             active_tool_id: null,
             active_chat_tool_id: null,
             chat_providers: [],
-            env_status: null,
             tool_statuses: null,
             tool_configs: null,
             global_instructions: null,
@@ -206,9 +401,25 @@ export const openUrl = async (url: string): Promise<void> => {
     }
 };
 
-export const ptyOpen = async (id: string, cols: number, rows: number): Promise<void> => {
+export interface PtyOpenOptions {
+    /** Id of a saved terminal profile; explicit fields below override it */
+    profile?: string;
+    /** Program to run instead of the configured `terminal_shell` */
+    program?: string;
+    args?: string[];
+    cwd?: string;
+    env?: Record<string, string>;
+    /** Record output to an asciinema v2 `.cast` file under the app data dir */
+    record?: boolean;
+    /** Also record keystrokes sent through `pty_write` */
+    recordInput?: boolean;
+    /** Tool id whose ToolConfig working directory and API key/base URL/model env vars apply */
+    tool?: string;
+}
+
+export const ptyOpen = async (id: string, cols: number, rows: number, options: PtyOpenOptions = {}): Promise<void> => {
     try {
-        await invoke('pty_open', { id, cols, rows });
+        await invoke('pty_open', { id, cols, rows, options });
     } catch (e) {
         console.warn("PTY Open failed", e);
     }
@@ -247,6 +458,90 @@ export const ptyExists = async (id: string): Promise<boolean> => {
     }
 };
 
+export interface PtyAttach {
+    id: string;
+    data: number[];
+    offset: number;
+}
+
+/** Returns the buffered scrollback of a live session so a remounted terminal can replay it. */
+export const ptyAttach = async (id: string): Promise<PtyAttach | null> => {
+    try {
+        return await invoke<PtyAttach>('pty_attach', { id });
+    } catch (e) {
+        console.warn("PTY Attach failed", e);
+        return null;
+    }
+};
+
+export interface PtyExitStatus {
+    exit_code: number;
+    signal: string | null;
+    success: boolean;
+}
+
+export interface PtyStatus {
+    id: string;
+    running: boolean;
+    pid: number | null;
+    exit: PtyExitStatus | null;
+}
+
+/** Ended sessions are also announced through `pty-exit` events ({ id, exitCode, signal, success }). */
+export const ptyStatus = async (id: string): Promise<PtyStatus | null> => {
+    try {
+        return await invoke<PtyStatus>('pty_status', { id });
+    } catch (e) {
+        console.warn("PTY Status check failed", e);
+        return null;
+    }
+};
+
+export const getTerminalProfiles = async (): Promise<TerminalProfile[]> => {
+    try {
+        return await invoke('get_terminal_profiles');
+    } catch (e) {
+        console.warn("Get Terminal Profiles failed (Browser Mode)", e);
+        return [];
+    }
+};
+
+export const saveTerminalProfiles = async (profiles: TerminalProfile[]): Promise<void> => {
+    await invoke('save_terminal_profiles', { profiles });
+};
+
+export interface RecordingInfo {
+    name: string;
+    path: string;
+    size: number;
+    timestamp: number | null;
+    width: number | null;
+    height: number | null;
+    title: string | null;
+}
+
+export const listRecordings = async (): Promise<RecordingInfo[]> => {
+    try {
+        return await invoke('list_recordings');
+    } catch (e) {
+        console.warn("List Recordings failed (Browser Mode)", e);
+        return [];
+    }
+};
+
+/**
+ * Replays into terminal `id` as `pty-data` events; `pty-replay-done` fires at the end
+ * (with `cancelled: true` if `ptyClose(id)` or another replay into `id` stopped it).
+ * `speed` is a multiplier between 0.1 and 100 (default 1).
+ */
+export const replayRecording = async (name: string, id: string, speed?: number): Promise<void> => {
+    await invoke('replay_recording', { name, id, speed });
+};
+
+export const deleteRecording = async (name: string): Promise<void> => {
+    await invoke('delete_recording', { name });
+};
+
 export const getChatSessions = async (): Promise<ChatSession[]> => {
     try {
         return await invoke('get_chat_sessions');
@@ -264,9 +559,10 @@ export const saveChatSessions = async (sessions: any[]): Promise<void> => {
     }
 };
 
-export const fetchRemoteModels = async (baseUrl: string, apiKey: string): Promise<string[]> => {
+/** An empty `baseUrl` uses the provider's default endpoint. A stored `apiKeyId` is only sent to its tool config's saved base URL */
+export const fetchRemoteModels = async (baseUrl: string, apiKey: string, apiKeyId?: string, apiType?: ChatApiType): Promise<string[]> => {
     try {
-        return await invoke('fetch_remote_models', { baseUrl, apiKey });
+        return await invoke('fetch_remote_models', { baseUrl, apiKey, apiKeyId, apiType });
     } catch (e) {
         console.warn("Fetch Models failed (Browser Mode)", e);
         // Mock data for browser
@@ -274,6 +570,26 @@ export const fetchRemoteModels = async (baseUrl: string, apiKey: string): Promis
     }
 };
 
+/** Id a tool config's API key is stored under, matching the backend's `tool_api_key_id` */
+export const toolApiKeyId = (toolId: string): string => `tool_configs.${toolId}.llm_api_key`;
+
+export const setSecret = async (id: string, value: string): Promise<void> => {
+    await invoke('set_secret', { id, value });
+};
+
+export const deleteSecret = async (id: string): Promise<void> => {
+    await invoke('delete_secret', { id });
+};
+
+export const hasSecret = async (id: string): Promise<boolean> => {
+    try {
+        return await invoke('has_secret', { id });
+    } catch (e) {
+        console.warn("Secret lookup failed (Browser Mode)", e);
+        return false;
+    }
+};
+
 export interface SearchResult {
     file: string;
     line: number | null;
@@ -348,15 +664,60 @@ export const getConfig = async (path: string): Promise<string> => {
     }
 }
 
-export const saveConfig = async (path: string, content: string): Promise<void> => {
+export type ConfigFormat = 'json' | 'yaml' | 'toml';
+
+/** Parse/edit failure; line and column are 1-based when the parser knows them */
+export interface ConfigFileError {
+    message: string;
+    line: number | null;
+    column: number | null;
+}
+
+/** Rejects with a ConfigFileError when the content doesn't parse as `format` (or the format implied by the extension) */
+export const saveConfig = async (path: string, content: string, format?: ConfigFormat): Promise<void> => {
     try {
-        await invoke('save_config_file', { path, content });
+        await invoke('save_config_file', { path, content, format });
     } catch (e) {
         console.warn("Save Config failed (Browser Mode)", e);
         throw e;
     }
 }
 
+/** Message for a rejected config save or edit, with the line and column when the parser reported them */
+export const formatConfigFileError = (e: unknown): string => {
+    if (typeof e !== 'object' || e === null || !('message' in e)) {
+        return String(e);
+    }
+    const { message, line, column } = e as ConfigFileError;
+    if (line != null && column != null) return `line ${line}, column ${column}: ${message}`;
+    if (line != null) return `line ${line}: ${message}`;
+    return message;
+}
+
+/** Resolves to null when the content is valid */
+export const validateConfigFile = async (path: string, content: string, format?: ConfigFormat): Promise<ConfigFileError | null> => {
+    try {
+        await invoke('validate_config_file', { path, content, format });
+        return null;
+    } catch (e) {
+        if (typeof e === 'object' && e !== null && 'message' in e) {
+            return e as ConfigFileError;
+        }
+        console.warn("Validate Config failed (Browser Mode)", e);
+        return null;
+    }
+}
+
+/** `key` is a JSON-pointer-like path, e.g. "/mcpServers/github/command" */
+export const getConfigValue = async <T = unknown>(path: string, key: string, format?: ConfigFormat): Promise<T | null> => {
+    return await invoke<T | null>('get_config_value', { path, key, format });
+}
+
+/** Edits a single key in place; a null value removes it. Rejects with a ConfigFileError */
+export const setConfigValue = async (path: string, key: string, value: unknown, format?: ConfigFormat): Promise<void> => {
+    await invoke('set_config_value', { path, key, value, format });
+}
+
 export const uninstallSkills = async (path: string): Promise<void> => {
     try {
         await invoke('uninstall_skills', { path });
@@ -415,20 +776,90 @@ export interface LLMMessage {
     content: string;
 }
 
+/** How long to wait for trailing `chat-stream` events after the reply has returned */
+const CHAT_DONE_TIMEOUT_MS = 5000;
+
+export type ChatApiType = 'openai' | 'anthropic' | 'google' | 'ollama';
+
+export interface ChatStreamEvent {
+    id: string;
+    delta: string;
+    done: boolean;
+    error?: string | null;
+}
+
 /**
  * Chat completion proxy via Rust backend (#11).
  * The API key is handled server-side and never exposed to the renderer process.
+ * Deltas arrive as `chat-stream` events keyed by request id; the full reply is returned at the end.
+ * `apiType` selects the native Anthropic / Gemini / Ollama wire format instead of OpenAI chat completions.
+ * For `ollama` an empty `baseUrl` falls back to `local_ai_base_url` from the app config.
+ * A stored `apiKeyId` is rejected unless `baseUrl` matches the one saved with its tool config.
  */
 export const chatCompletion = async (
     apiKey: string,
     baseUrl: string,
     model: string,
     messages: LLMMessage[],
+    onDelta?: (delta: string) => void,
+    apiType?: ChatApiType,
+    apiKeyId?: string,
 ): Promise<string> => {
-    return await invoke<string>('chat_completion', {
-        apiKey,
-        baseUrl,
-        model,
-        messages,
+    const requestId = `${Date.now()}-${Math.random().toString(36).slice(2, 8)}`;
+    // Events aren't ordered against the invoke response, so keep listening until
+    // the backend's `done` event: it is emitted after the last delta.
+    let markDone: () => void = () => {};
+    const done = new Promise<void>((resolve) => { markDone = resolve; });
+    const unlisten = await listen<ChatStreamEvent>('chat-stream', (event) => {
+        if (event.payload.id !== requestId) return;
+        if (event.payload.delta) {
+            onDelta?.(event.payload.delta);
+        }
+        if (event.payload.done) {
+            markDone();
+        }
     });
+
+    try {
+        return await invoke<string>('chat_completion', {
+            apiKey,
+            baseUrl,
+            model,
+            messages,
+            apiType,
+            requestId,
+            apiKeyId,
+        });
+    } finally {
+        // Don't hang forever if the event was lost entirely
+        await Promise.race([done, new Promise((resolve) => setTimeout(resolve, CHAT_DONE_TIMEOUT_MS))]);
+        unlisten();
+    }
+}
+
+export interface LocalModel {
+    name: string;
+    size: number | null;
+    digest: string | null;
+    modified_at: string | null;
+    parameter_size: string | null;
+    quantization_level: string | null;
+}
+
+export const ollamaListModels = async (): Promise<LocalModel[]> => {
+    try {
+        return await invoke('ollama_list_models');
+    } catch (e) {
+        console.warn("List Ollama Models failed", e);
+        return [];
+    }
+}
+
+/** Progress is reported through `ollama-pull-progress` events. */
+export const ollamaPullModel = async (model: string): Promise<string> => {
+    return await invoke<string>('ollama_pull_model', { model });
+}
+
+export const ollamaDeleteModel = async (model: string): Promise<void> => {
+    await invoke('ollama_delete_model', { model });
 }
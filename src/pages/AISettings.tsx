@@ -20,7 +20,7 @@ import SkillMonitor from '../components/SkillMonitor';
 import McpMonitor from '../components/McpMonitor';
 import PluginManagement from '../components/PluginManagement';
 import { TOOLS_METADATA } from '../constants/tools';
-import type { McpInfo } from '../lib/tauri';
+import { formatConfigFileError, type McpInfo } from '../lib/tauri';
 
 const { Title } = Typography;
 
@@ -190,7 +190,7 @@ const AISettings = () => {
             await invoke('save_config_file', { path: tool.configPath, content: configContent });
             message.success(t('aiSettings.mcpConfig.saved', 'Saved config for ') + tool.displayName);
         } catch (error) {
-            message.error(t('aiSettings.mcpConfig.saveFailed', 'Failed to save config: ') + error);
+            message.error(t('aiSettings.mcpConfig.saveFailed', 'Failed to save config: ') + formatConfigFileError(error));
         }
     };
 
@@ -334,7 +334,7 @@ const AISettings = () => {
             setEditModalVisible(false);
             setEditingMcp(null);
         } catch (error) {
-            message.error(t('aiSettings.mcpConfig.saveFailed', 'Save failed: ') + error);
+            message.error(t('aiSettings.mcpConfig.saveFailed', 'Save failed: ') + formatConfigFileError(error));
         }
     };
 
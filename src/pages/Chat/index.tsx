@@ -11,7 +11,7 @@ import { useChatStore } from '../../store/chatStore';
 import WorkspaceSider from '../../components/WorkspaceSider';
 import ResizableSider from '../../components/ResizableSider';
 import { useTranslation } from 'react-i18next';
-import { chatCompletion } from '../../lib/tauri'; // #11: API calls proxied via Rust backend
+import { chatCompletion, ollamaListModels } from '../../lib/tauri'; // #11: API calls proxied via Rust backend
 
 const { Content, Header } = Layout;
 const { Title, Text } = Typography;
@@ -45,6 +45,15 @@ const ChatPage: React.FC = () => {
 
     const [isResizing, setIsResizing] = useState(false);
 
+    // Models installed in the local Ollama, for the session model picker
+    const activeApiType = activeChatToolId ? toolConfigs[activeChatToolId]?.llmApiType : undefined;
+    const [localModels, setLocalModels] = useState<string[]>([]);
+
+    useEffect(() => {
+        if (activeApiType !== 'ollama') return;
+        ollamaListModels().then((models) => setLocalModels(models.map((m) => m.name)));
+    }, [activeApiType, activeChatToolId]);
+
     useEffect(() => {
         const handleMouseMove = (e: MouseEvent) => {
             if (!isResizing) return;
@@ -128,10 +137,16 @@ const ChatPage: React.FC = () => {
 
         const activeToolConfig = activeChatToolId ? toolConfigs[activeChatToolId] : undefined;
         const llmApiKey = activeToolConfig?.llmApiKey || '';
-        const llmModel = activeToolConfig?.llmModel || 'gpt-4o-mini';
-        const localAiBaseUrl = activeToolConfig?.llmBaseUrl || '';
-
-        if (!llmApiKey && !localAiBaseUrl) {
+        const llmApiKeyId = activeToolConfig?.llmApiKeyId;
+        const llmApiType = activeToolConfig?.llmApiType;
+        const isOpenAiCompatible = !llmApiType || llmApiType === 'openai';
+        const llmModel = activeToolConfig?.llmModel || (isOpenAiCompatible ? 'gpt-4o-mini' : '');
+        // Empty means the provider's default endpoint, resolved by the backend
+        const llmBaseUrl = activeToolConfig?.llmBaseUrl || '';
+
+        // OpenAI-compatible servers on a custom URL often run without a key
+        const needsKey = llmApiType !== 'ollama' && !(isOpenAiCompatible && llmBaseUrl);
+        if (needsKey && !llmApiKey && !llmApiKeyId) {
             useChatStore.getState().updateLastMessage(activeSessionId, t('chat.noApiKeyWarning', '⚠️ Please set your LLM API Key and Base URL by clicking the Settings icon in the top right.'));
             return;
         }
@@ -141,8 +156,6 @@ const ChatPage: React.FC = () => {
         const sessionMessages = useChatStore.getState().messages[activeSessionId] || [];
 
         try {
-            const apiBaseUrl = localAiBaseUrl || 'https://api.openai.com/v1';
-
             // Build OpenAI-compatible messages array
             const openAiMessages = [...sessionMessages, { type: 'user', content: combinedText }].map(msg => ({
                 role: msg.type === 'user' ? 'user' : 'assistant',
@@ -153,14 +166,25 @@ const ChatPage: React.FC = () => {
             addMessage(activeSessionId, { type: 'assistant', content: '', id: (Date.now() + 1).toString() });
 
             // #11: Route through Rust backend proxy — API key never touches the renderer's network layer
+            let streamed = false;
             const reply = await chatCompletion(
                 llmApiKey,
-                apiBaseUrl,
-                llmModel || 'gpt-4o-mini',
+                llmBaseUrl,
+                llmModel,
                 openAiMessages as any,
+                (delta) => {
+                    streamed = true;
+                    useChatStore.getState().appendToLastMessage(activeSessionId, delta);
+                },
+                llmApiType,
+                llmApiKeyId,
             );
 
-            useChatStore.getState().updateLastMessage(activeSessionId, reply);
+            if (streamed) {
+                useChatStore.getState().saveSessions();
+            } else {
+                useChatStore.getState().updateLastMessage(activeSessionId, reply);
+            }
         } catch (error: any) {
             console.error('LLM Request Error', error);
             useChatStore.getState().updateLastMessage(
@@ -263,6 +287,19 @@ const ChatPage: React.FC = () => {
                                     {t('chat.configTitle', 'Session Configuration')}
                                 </Text>
                                 <Space direction="vertical" style={{ width: '100%' }} size={12}>
+                                    {activeApiType === 'ollama' && activeChatToolId && (
+                                        <div>
+                                            <Text type="secondary" style={{ fontSize: 11, display: 'block', marginBottom: 4 }}>{t('chat.localModel', 'Local Model')}</Text>
+                                            <Select
+                                                size="small"
+                                                style={{ width: '100%' }}
+                                                value={toolConfigs[activeChatToolId]?.llmModel || undefined}
+                                                placeholder={t('chat.noLocalModels', 'No local models found')}
+                                                onChange={(val) => setToolConfig(activeChatToolId, { llmModel: val })}
+                                                options={localModels.map((m) => ({ value: m, label: m }))}
+                                            />
+                                        </div>
+                                    )}
                                     <div>
                                         <Text type="secondary" style={{ fontSize: 11, display: 'block', marginBottom: 4 }}>{t('chat.chatType', 'Chat Type')}</Text>
                                         <Select
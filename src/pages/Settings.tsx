@@ -10,8 +10,8 @@ import { useTranslation } from 'react-i18next';
 import { BgColorsOutlined, GlobalOutlined, PlusOutlined, DesktopOutlined, ThunderboltOutlined, SettingOutlined } from '@ant-design/icons';
 import { open } from '@tauri-apps/plugin-dialog';
 import { useAppStore } from '../store/appStore';
-import { useState, useEffect } from 'react';
-import { checkEnvironment, type EnvironmentStatus } from '../lib/tauri';
+import { useState, useEffect, type ReactNode } from 'react';
+import { checkEnvironment, listEnvironmentProbes, type EnvironmentStatus, type ProbeDefinition, type ProbeResult } from '../lib/tauri';
 import { ReloadOutlined, CheckCircleOutlined, WarningOutlined, CodeOutlined } from '@ant-design/icons';
 import { terminalPresets } from '../constants/terminal';
 import { languageList } from '../constants/i18n';
@@ -470,13 +470,15 @@ const Settings = () => {
 
     const EnvironmentSettings = () => {
         const [status, setStatus] = useState<EnvironmentStatus | null>(null);
+        const [probes, setProbes] = useState<ProbeDefinition[]>([]);
         const [loading, setLoading] = useState(false);
 
         const loadStatus = async () => {
             setLoading(true);
             try {
-                const s = await checkEnvironment();
+                const [s, defs] = await Promise.all([checkEnvironment(), listEnvironmentProbes()]);
                 setStatus(s);
+                setProbes(defs);
             } finally {
                 setLoading(false);
             }
@@ -486,19 +488,19 @@ const Settings = () => {
             loadStatus();
         }, []);
 
-        const tools = [
-            { key: 'node_version', label: 'Node.js', icon: <DesktopOutlined /> },
-            { key: 'npm_version', label: 'NPM', icon: <DesktopOutlined /> },
-            { key: 'git_version', label: 'Git', icon: <DesktopOutlined /> },
-            { key: 'python_version', label: 'Python', icon: <DesktopOutlined /> },
-            { key: 'go_version', label: 'Go', icon: <DesktopOutlined /> },
-            { key: 'java_version', label: 'Java', icon: <DesktopOutlined /> },
-            { key: 'gh_version', label: 'GitHub CLI', icon: <GlobalOutlined /> },
-            { key: 'claude_version', label: 'Claude CLI', icon: <ThunderboltOutlined /> },
-            { key: 'opencode_version', label: 'OpenCode CLI', icon: <CodeOutlined /> },
-            { key: 'qoder_version', label: 'Qoder', icon: <CodeOutlined /> },
-            { key: 'codebuddy_version', label: 'CodeBuddy', icon: <CodeOutlined /> },
-        ];
+        const probeIcons: Record<string, ReactNode> = {
+            gh: <GlobalOutlined />,
+            claude: <ThunderboltOutlined />,
+            opencode: <CodeOutlined />,
+            qoder: <CodeOutlined />,
+            codebuddy: <CodeOutlined />,
+        };
+
+        const tools = probes.map(probe => ({
+            key: probe.name,
+            label: probe.label || probe.name,
+            icon: probeIcons[probe.name] || <DesktopOutlined />,
+        }));
 
         return (
             <div style={{ padding: 24 }}>
@@ -511,7 +513,8 @@ const Settings = () => {
 
                 <Row gutter={[16, 16]}>
                     {tools.map(tool => {
-                        const version = status ? (status as any)[tool.key] : null;
+                        const probe: ProbeResult | null = status?.probes?.[tool.key] ?? null;
+                        const version = probe?.version;
                         return (
                             <Col xs={24} sm={12} md={8} key={tool.key}>
                                 <Card size="small" hoverable>
@@ -523,7 +526,11 @@ const Settings = () => {
                                             <div style={{ fontWeight: 500 }}>{tool.label}</div>
                                             <div style={{ color: version ? 'rgba(0,0,0,0.45)' : '#faad14', fontSize: 12, display: 'flex', justifyContent: 'space-between' }}>
                                                 <span style={{ textOverflow: 'ellipsis', overflow: 'hidden', whiteSpace: 'nowrap' }}>
-                                                    {version || t('settings.notInstalled', 'Not Installed')}
+                                                    {version || (probe?.status === 'timed_out'
+                                                        ? t('settings.probeTimedOut', 'Timed Out')
+                                                        : probe?.status === 'error'
+                                                            ? probe.error
+                                                            : t('settings.notInstalled', 'Not Installed'))}
                                                 </span>
                                             </div>
                                         </div>
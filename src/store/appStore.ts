@@ -7,14 +7,17 @@
 
 import { create } from 'zustand';
 import { persist, type PersistStorage } from 'zustand/middleware';
-import { checkEnvironment, checkToolStatus, getAppConfig, saveAppConfig, allowDirectory, type EnvironmentStatus, type ToolStatus } from '../lib/tauri';
+import { checkEnvironment, checkToolStatus, getAppConfig, saveAppConfig, allowDirectory, toolApiKeyId, type ChatApiType, type EnvironmentStatus, type ToolStatus } from '../lib/tauri';
 import { loadLanguage } from '../i18n';
 
 export interface ToolConfig {
     working_directory: string | null;
     llmApiKey?: string;
+    /** Id of the API key in the backend secret store (see `toolApiKeyId`) */
+    llmApiKeyId?: string;
     llmBaseUrl?: string;
     llmModel?: string;
+    llmApiType?: ChatApiType;
 }
 
 export interface LLMConfig {
@@ -139,7 +142,7 @@ const tauriStorage: PersistStorage<AppState> = {
                 currentDirectory: config.current_directory || null,
                 activeToolId: config.active_tool_id || null,
                 activeChatToolId: config.active_chat_tool_id || null,
-                envStatus: config.env_status || null,
+                envStatus: null,
                 toolStatuses: config.tool_statuses || {},
                 toolConfigs: (config.tool_configs as any) || {},
                 globalInstructions: config.global_instructions || null,
@@ -193,7 +196,6 @@ const tauriStorage: PersistStorage<AppState> = {
                 current_directory: state.currentDirectory,
                 active_tool_id: state.activeToolId,
                 active_chat_tool_id: state.activeChatToolId,
-                env_status: state.envStatus,
                 tool_statuses: state.toolStatuses,
                 tool_configs: state.toolConfigs as any,
                 global_instructions: state.globalInstructions,
@@ -205,6 +207,14 @@ const tauriStorage: PersistStorage<AppState> = {
                 chat_sidebar_width: state.chatSidebarWidth,
                 resource_sidebar_width: state.resourceSidebarWidth,
             });
+            // The backend moved any plaintext keys to the secret store; drop our
+            // copies so they aren't sent again with every save
+            const { setToolConfig } = useAppStore.getState();
+            for (const [toolId, config] of Object.entries(state.toolConfigs || {})) {
+                if (config?.llmApiKey) {
+                    setToolConfig(toolId, { llmApiKey: undefined, llmApiKeyId: toolApiKeyId(toolId) });
+                }
+            }
         } catch (e) {
             console.error('Failed to save config to Tauri', e);
         }
@@ -30,6 +30,8 @@ interface ChatState {
     clearAllSessions: () => void;
     addMessage: (sessionId: string, message: Omit<Message, 'timestamp'>) => void;
     updateLastMessage: (sessionId: string, appendContent: string) => void;
+    appendToLastMessage: (sessionId: string, appendContent: string) => void;
+    saveSessions: () => void;
     updateSessionTitle: (sessionId: string, title: string) => void;
     updateSessionConfig: (sessionId: string, config: Partial<ChatSession['config']>) => void;
 }
@@ -47,6 +49,50 @@ const persistSessions = async (state: ChatState) => {
     }
 };
 
+/** Appends to the session's last assistant message, or starts one */
+const appendToLast = (state: ChatState, sessionId: string, appendContent: string): Partial<ChatState> => {
+    const sessionMessages = state.messages[sessionId] || [];
+
+    const newSessions = state.sessions.map(s =>
+        s.id === sessionId ? { ...s, updatedAt: Date.now() } : s
+    );
+
+    if (sessionMessages.length === 0) {
+        return {
+            messages: {
+                ...state.messages,
+                [sessionId]: [{ id: Date.now().toString(), type: 'assistant', content: appendContent, timestamp: Date.now() }]
+            },
+            sessions: newSessions
+        };
+    }
+
+    const lastMessage = sessionMessages[sessionMessages.length - 1];
+    if (lastMessage.type !== 'assistant') {
+        return {
+            messages: {
+                ...state.messages,
+                [sessionId]: [...sessionMessages, { id: Date.now().toString(), type: 'assistant', content: appendContent, timestamp: Date.now() }]
+            },
+            sessions: newSessions
+        };
+    }
+
+    const updatedMessage = {
+        ...lastMessage,
+        content: lastMessage.content + appendContent,
+        timestamp: Date.now(),
+    };
+
+    return {
+        messages: {
+            ...state.messages,
+            [sessionId]: [...sessionMessages.slice(0, -1), updatedMessage]
+        },
+        sessions: newSessions
+    };
+};
+
 export const useChatStore = create<ChatState>((set, get) => ({
     sessions: [],
     messages: {},
@@ -161,48 +207,17 @@ export const useChatStore = create<ChatState>((set, get) => ({
     },
 
     updateLastMessage: (sessionId: string, appendContent: string) => {
-        set((state) => {
-            const sessionMessages = state.messages[sessionId] || [];
-
-            const newSessions = state.sessions.map(s =>
-                s.id === sessionId ? { ...s, updatedAt: Date.now() } : s
-            );
-
-            if (sessionMessages.length === 0) {
-                return {
-                    messages: {
-                        ...state.messages,
-                        [sessionId]: [{ id: Date.now().toString(), type: 'assistant', content: appendContent, timestamp: Date.now() }]
-                    },
-                    sessions: newSessions
-                };
-            }
-
-            const lastMessage = sessionMessages[sessionMessages.length - 1];
-            if (lastMessage.type !== 'assistant') {
-                return {
-                    messages: {
-                        ...state.messages,
-                        [sessionId]: [...sessionMessages, { id: Date.now().toString(), type: 'assistant', content: appendContent, timestamp: Date.now() }]
-                    },
-                    sessions: newSessions
-                };
-            }
+        set((state) => appendToLast(state, sessionId, appendContent));
+        persistSessions(get());
+    },
 
-            const updatedMessage = {
-                ...lastMessage,
-                content: lastMessage.content + appendContent,
-                timestamp: Date.now(),
-            };
+    // Streaming deltas only update memory; saving every token would rewrite and
+    // fsync the sessions file hundreds of times per reply. Call saveSessions after.
+    appendToLastMessage: (sessionId: string, appendContent: string) => {
+        set((state) => appendToLast(state, sessionId, appendContent));
+    },
 
-            return {
-                messages: {
-                    ...state.messages,
-                    [sessionId]: [...sessionMessages.slice(0, -1), updatedMessage]
-                },
-                sessions: newSessions
-            };
-        });
+    saveSessions: () => {
         persistSessions(get());
     },
 